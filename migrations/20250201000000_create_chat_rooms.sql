@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS chat_rooms (
+    room_id VARCHAR(36) PRIMARY KEY,
+    customer_id BIGINT NOT NULL REFERENCES users (user_id),
+    agent_id BIGINT REFERENCES users (user_id),
+    status VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_rooms_status ON chat_rooms (status);
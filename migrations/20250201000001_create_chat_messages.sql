@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS chat_messages (
+    message_id BIGSERIAL PRIMARY KEY,
+    room_id VARCHAR(36) NOT NULL REFERENCES chat_rooms (room_id),
+    sender_id BIGINT NOT NULL REFERENCES users (user_id),
+    sender_role VARCHAR(20) NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_messages_room_id ON chat_messages (room_id, message_id);
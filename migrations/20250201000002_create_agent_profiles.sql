@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS agent_profiles (
+    agent_id BIGINT PRIMARY KEY REFERENCES users (user_id),
+    max_concurrent_chats INT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
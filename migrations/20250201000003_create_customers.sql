@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS customers (
+    customer_id BIGINT PRIMARY KEY REFERENCES users (user_id),
+    phone VARCHAR(30),
+    locale VARCHAR(20),
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_rooms_customer_id ON chat_rooms (customer_id, created_at);
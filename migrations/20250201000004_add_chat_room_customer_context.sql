@@ -0,0 +1,4 @@
+ALTER TABLE chat_rooms
+    ADD COLUMN IF NOT EXISTS customer_name VARCHAR(100) NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS customer_email VARCHAR(255) NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS assigned_at TIMESTAMPTZ;
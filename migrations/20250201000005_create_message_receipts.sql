@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS message_receipts (
+    message_id BIGINT NOT NULL REFERENCES chat_messages (message_id),
+    user_id BIGINT NOT NULL REFERENCES users (user_id),
+    delivered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMPTZ,
+    PRIMARY KEY (message_id, user_id)
+);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS chat_attachments (
+    attachment_id VARCHAR(36) PRIMARY KEY,
+    room_id VARCHAR(36) NOT NULL REFERENCES chat_rooms (room_id),
+    uploader_id BIGINT NOT NULL REFERENCES users (user_id),
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    storage_key VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE chat_messages
+    ADD COLUMN IF NOT EXISTS attachment_id VARCHAR(36) REFERENCES chat_attachments (attachment_id);
@@ -0,0 +1,14 @@
+ALTER TABLE chat_rooms
+    ADD COLUMN IF NOT EXISTS ended_by VARCHAR(20),
+    ADD COLUMN IF NOT EXISTS ended_by_user_id BIGINT REFERENCES users (user_id),
+    ADD COLUMN IF NOT EXISTS end_reason VARCHAR(200),
+    ADD COLUMN IF NOT EXISTS summary TEXT,
+    ADD COLUMN IF NOT EXISTS ended_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS chat_surveys (
+    room_id VARCHAR(36) PRIMARY KEY REFERENCES chat_rooms (room_id),
+    customer_id BIGINT NOT NULL REFERENCES users (user_id),
+    rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS chat_room_transfers (
+    transfer_id BIGSERIAL PRIMARY KEY,
+    room_id VARCHAR(36) NOT NULL REFERENCES chat_rooms (room_id),
+    from_agent_id BIGINT NOT NULL REFERENCES users (user_id),
+    to_agent_id BIGINT REFERENCES users (user_id),
+    note TEXT,
+    escalated BOOLEAN NOT NULL DEFAULT FALSE,
+    transferred_by BIGINT NOT NULL REFERENCES users (user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_room_transfers_room_id ON chat_room_transfers (room_id, transfer_id);
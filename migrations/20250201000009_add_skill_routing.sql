@@ -0,0 +1,11 @@
+ALTER TABLE chat_rooms
+    ADD COLUMN IF NOT EXISTS topic VARCHAR(50),
+    ADD COLUMN IF NOT EXISTS language VARCHAR(50);
+
+CREATE TABLE IF NOT EXISTS agent_skills (
+    agent_id BIGINT NOT NULL REFERENCES users (user_id),
+    skill VARCHAR(50) NOT NULL,
+    proficiency SMALLINT NOT NULL CHECK (proficiency BETWEEN 1 AND 5),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (agent_id, skill)
+);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS chat_queues (
+    name VARCHAR(50) PRIMARY KEY,
+    priority INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO chat_queues (name, priority) VALUES ('default', 0) ON CONFLICT (name) DO NOTHING;
+
+ALTER TABLE chat_rooms
+    ADD COLUMN IF NOT EXISTS queue VARCHAR(50) NOT NULL DEFAULT 'default' REFERENCES chat_queues (name),
+    ADD COLUMN IF NOT EXISTS priority INT NOT NULL DEFAULT 0;
+
+ALTER TABLE customers
+    ADD COLUMN IF NOT EXISTS tier VARCHAR(20) NOT NULL DEFAULT 'standard';
+
+CREATE TABLE IF NOT EXISTS agent_queues (
+    agent_id BIGINT NOT NULL REFERENCES users (user_id),
+    queue VARCHAR(50) NOT NULL REFERENCES chat_queues (name),
+    PRIMARY KEY (agent_id, queue)
+);
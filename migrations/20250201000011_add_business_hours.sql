@@ -0,0 +1,39 @@
+ALTER TABLE chat_queues
+    ADD COLUMN IF NOT EXISTS time_zone VARCHAR(64) NOT NULL DEFAULT 'UTC';
+
+-- 영업 시간이 하나도 없는 대기열은 항상 영업한다 (weekday 0 = 월요일)
+CREATE TABLE IF NOT EXISTS queue_business_hours (
+    queue VARCHAR(50) NOT NULL REFERENCES chat_queues (name),
+    weekday SMALLINT NOT NULL CHECK (weekday BETWEEN 0 AND 6),
+    open_time TIME NOT NULL,
+    close_time TIME NOT NULL,
+    PRIMARY KEY (queue, weekday, open_time),
+    CHECK (open_time < close_time)
+);
+
+-- queue 가 NULL 이면 모든 대기열의 휴일
+CREATE TABLE IF NOT EXISTS holidays (
+    holiday_id BIGSERIAL PRIMARY KEY,
+    queue VARCHAR(50) REFERENCES chat_queues (name),
+    holiday DATE NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_holidays_queue_holiday ON holidays (COALESCE(queue, ''), holiday);
+
+-- 영업 시간 밖에 남긴 문의 (다음 근무 때 상담원이 가져간다)
+CREATE TABLE IF NOT EXISTS offline_messages (
+    offline_message_id BIGSERIAL PRIMARY KEY,
+    customer_id BIGINT NOT NULL REFERENCES users (user_id),
+    queue VARCHAR(50) NOT NULL REFERENCES chat_queues (name),
+    topic VARCHAR(50),
+    language VARCHAR(50),
+    body TEXT NOT NULL,
+    picked_by BIGINT REFERENCES users (user_id),
+    picked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_offline_messages_pending ON offline_messages (queue, offline_message_id)
+    WHERE picked_by IS NULL;
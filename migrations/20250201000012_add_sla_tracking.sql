@@ -0,0 +1,33 @@
+-- assigned_at 은 방을 넘길 때마다 바뀌므로 SLA 는 처음 배정된 시각 기준으로 잰다
+ALTER TABLE chat_rooms
+    ADD COLUMN IF NOT EXISTS first_assigned_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS first_response_at TIMESTAMPTZ;
+
+UPDATE chat_rooms
+SET first_assigned_at = assigned_at
+WHERE first_assigned_at IS NULL AND assigned_at IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_chat_rooms_created_at ON chat_rooms (created_at);
+
+-- 대기열별 SLA 목표 (초, NULL 이면 측정만 하고 위반으로 보지 않는다)
+CREATE TABLE IF NOT EXISTS queue_sla_targets (
+    queue VARCHAR(50) PRIMARY KEY REFERENCES chat_queues (name),
+    time_to_assign_secs INT CHECK (time_to_assign_secs > 0),
+    first_response_secs INT CHECK (first_response_secs > 0),
+    handle_time_secs INT CHECK (handle_time_secs > 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 방마다 지표별로 한 번만 기록한다 (breached_at 은 목표를 넘긴 시각)
+CREATE TABLE IF NOT EXISTS sla_breaches (
+    room_id VARCHAR(36) NOT NULL REFERENCES chat_rooms (room_id),
+    metric VARCHAR(20) NOT NULL,
+    queue VARCHAR(50) NOT NULL REFERENCES chat_queues (name),
+    agent_id BIGINT REFERENCES users (user_id),
+    target_secs INT NOT NULL,
+    breached_at TIMESTAMPTZ NOT NULL,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (room_id, metric)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sla_breaches_breached_at ON sla_breaches (breached_at DESC);
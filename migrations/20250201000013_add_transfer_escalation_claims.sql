@@ -0,0 +1,8 @@
+-- 이관된 방을 맡은 팀장 (escalated 인 기록만 채워진다)
+ALTER TABLE chat_room_transfers
+    ADD COLUMN IF NOT EXISTS claimed_by BIGINT REFERENCES users (user_id),
+    ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_chat_room_transfers_unclaimed
+    ON chat_room_transfers (transfer_id)
+    WHERE escalated AND claimed_by IS NULL;
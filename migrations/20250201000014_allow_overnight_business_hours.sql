@@ -0,0 +1,4 @@
+-- close_time 이 open_time 보다 이르면 다음 날 close_time 까지 영업한다 (예: 22:00 ~ 02:00)
+ALTER TABLE queue_business_hours DROP CONSTRAINT IF EXISTS queue_business_hours_check;
+ALTER TABLE queue_business_hours
+    ADD CONSTRAINT queue_business_hours_check CHECK (open_time <> close_time);
@@ -1,22 +1,46 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
-use tokio::sync::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, RwLock};
+use uuid::Uuid;
 
 use crate::{
-    chat::chatting::ChatRoomId,
+    chat::chatting::{chat_sla::SlaMetric, ChatRoomId},
     config::{error::AppError, MangJooResult},
 };
 
+use super::{agent_repository::AgentRepository, agent_store::AgentStore};
+
 // 상담원 정보
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Agent {
     pub agent_id: String,
     pub name: String,
     pub status: AgentStatus,
-    pub active_room_id: Option<ChatRoomId>, // 현재 상담 중인 방
+    pub active_room_ids: HashSet<ChatRoomId>, // 현재 상담 중인 방들
+    pub max_concurrent_chats: usize,          // 동시에 진행 가능한 상담 수
     pub last_active: DateTime<Utc>,
+    // 스킬 이름 (상담 주제 또는 언어) -> 숙련도 (1~5)
+    #[serde(default)]
+    pub skills: HashMap<String, u8>,
+    // 구독한 대기열 (비어 있으면 모든 대기열의 방을 받는다)
+    #[serde(default)]
+    pub queues: HashSet<String>,
+    // 마지막으로 방을 배정받은 시각과 상담을 마친 시각 (하트비트로는 바뀌지 않는다)
+    #[serde(default)]
+    pub last_assigned_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_chat_ended_at: Option<DateTime<Utc>>,
+    // 상담원이 직접 자리비움을 고른 경우 (활동이 없어 자리비움이 된 경우는 false)
+    #[serde(default)]
+    pub away_by_choice: bool,
+    // 상태 소켓이 연결된 인스턴스 -> 그 연결의 마지막 하트비트 시각
+    #[serde(default)]
+    pub connections: HashMap<String, DateTime<Utc>>,
 }
 
 impl Agent {
@@ -24,15 +48,23 @@ impl Agent {
         agent_id: String,
         name: String,
         status: AgentStatus,
-        active_room_id: Option<ChatRoomId>,
+        active_room_ids: HashSet<ChatRoomId>,
+        max_concurrent_chats: usize,
         last_active: DateTime<Utc>,
     ) -> Self {
         Self {
             agent_id,
             name,
             status,
-            active_room_id,
+            active_room_ids,
+            max_concurrent_chats,
             last_active,
+            skills: HashMap::new(),
+            queues: HashSet::new(),
+            last_assigned_at: None,
+            last_chat_ended_at: None,
+            away_by_choice: false,
+            connections: HashMap::new(),
         }
     }
 
@@ -40,37 +72,408 @@ impl Agent {
         self.status == AgentStatus::Available
     }
 
+    pub fn has_capacity(&self) -> bool {
+        self.active_room_ids.len() < self.max_concurrent_chats
+    }
+
+    pub fn proficiency(&self, skill: &str) -> Option<u8> {
+        self.skills.get(skill).copied()
+    }
+
+    pub fn serves_queue(&self, queue: &str) -> bool {
+        self.queues.is_empty() || self.queues.contains(queue)
+    }
+
+    // 마지막으로 방을 받거나 상담을 마친 시각 (None 이면 접속 후 아직 상담이 없다)
+    pub fn idle_since(&self) -> Option<DateTime<Utc>> {
+        self.last_assigned_at.max(self.last_chat_ended_at)
+    }
+
+    // since 이후에 하트비트가 들어온 연결이 남아 있는지 (멈춘 인스턴스의 연결은 제외된다)
+    pub fn has_live_connection(&self, since: DateTime<Utc>) -> bool {
+        self.connections
+            .values()
+            .any(|last_seen| *last_seen > since)
+    }
+
     pub fn update_agent_status(&mut self, agent_status: AgentStatus) {
         self.status = agent_status;
         self.last_active = Utc::now();
     }
+
+    // 최대 상담 수에 도달하면 자동으로 상담중 상태가 된다
+    pub fn assign_room(&mut self, room_id: ChatRoomId) {
+        self.active_room_ids.insert(room_id);
+        self.last_assigned_at = Some(Utc::now());
+        if !self.has_capacity() && self.is_available() {
+            self.update_agent_status(AgentStatus::Busy);
+        } else {
+            self.touch();
+        }
+    }
+
+    pub fn release_room(&mut self, room_id: &ChatRoomId) {
+        let was_full = !self.has_capacity();
+        if !self.active_room_ids.remove(room_id) {
+            return;
+        }
+        self.last_chat_ended_at = Some(Utc::now());
+        // 가득 차서 상담중이 된 경우에만 다시 대기 상태로 돌린다
+        if was_full && self.has_capacity() && self.status == AgentStatus::Busy {
+            self.update_agent_status(AgentStatus::Available);
+        }
+    }
+
+    pub fn update_max_concurrent_chats(&mut self, max_concurrent_chats: usize) {
+        self.max_concurrent_chats = max_concurrent_chats;
+        match self.status {
+            AgentStatus::Available if !self.has_capacity() => {
+                self.update_agent_status(AgentStatus::Busy)
+            }
+            AgentStatus::Busy if self.has_capacity() => {
+                self.update_agent_status(AgentStatus::Available)
+            }
+            _ => {}
+        }
+    }
+
+    pub fn touch(&mut self) {
+        self.last_active = Utc::now();
+    }
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum AgentStatus {
     Available,
     Busy,
     Away,
 }
 
+// 상담원에게 푸시되는 알림
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentNotification {
+    RoomAssigned {
+        room_id: ChatRoomId,
+        customer_id: i64,
+        assigned_at: DateTime<Utc>,
+    },
+    // 다른 상담원이 방을 넘겨준 경우 (note 는 상담원끼리만 공유된다)
+    RoomTransferred {
+        room_id: ChatRoomId,
+        customer_id: i64,
+        from_agent_id: i64,
+        note: Option<String>,
+        escalated: bool,
+        transferred_at: DateTime<Utc>,
+    },
+    StatusChanged {
+        status: AgentStatus,
+        changed_at: DateTime<Utc>,
+    },
+    // 근무를 시작할 때 영업 시간 밖에 밀린 문의 수
+    OfflineMessagesPending {
+        count: i64,
+        notified_at: DateTime<Utc>,
+    },
+    // 대기 중인 방은 대기열을 맡은 상담원에게, 배정된 방은 담당 상담원에게 알린다
+    SlaBreached {
+        room_id: ChatRoomId,
+        queue: String,
+        metric: SlaMetric,
+        target_secs: i32,
+        breached_at: DateTime<Utc>,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub struct Agents {
-    agents: Arc<RwLock<HashMap<i64, Agent>>>,
+    store: Arc<dyn AgentStore>,
+    // 상태 소켓은 인스턴스마다 따로 연결되므로 알림 채널은 로컬에 둔다
+    notifiers: Arc<RwLock<HashMap<i64, mpsc::UnboundedSender<AgentNotification>>>>,
+    // 상담원 정보에 이 인스턴스의 연결을 표시할 때 쓴다
+    instance_id: String,
+    repository: AgentRepository,
+    default_max_concurrent_chats: usize,
 }
 
 impl Agents {
-    pub fn new() -> Self {
+    pub fn new(
+        store: Arc<dyn AgentStore>,
+        repository: AgentRepository,
+        default_max_concurrent_chats: usize,
+    ) -> Self {
         Self {
-            agents: Arc::new(RwLock::new(HashMap::new())),
+            store,
+            notifiers: Arc::new(RwLock::new(HashMap::new())),
+            instance_id: Uuid::new_v4().to_string(),
+            repository,
+            default_max_concurrent_chats,
         }
     }
 
-    pub async fn find_available_agent(&self) -> Option<i64> {
-        let agents = self.agents.read().await;
-        agents
+    // 로그인한 상담원을 등록 (이미 등록된 경우 상태를 유지)
+    pub async fn register(&self, agent_id: i64, name: String) -> MangJooResult<()> {
+        if self.store.get(agent_id).await?.is_some() {
+            return Ok(());
+        }
+
+        let max_concurrent_chats = self.find_max_concurrent_chats(agent_id).await;
+        let mut agent = Agent::new(
+            agent_id.to_string(),
+            name,
+            AgentStatus::Away,
+            HashSet::new(),
+            max_concurrent_chats,
+            Utc::now(),
+        );
+        agent.skills = self.find_skills(agent_id).await;
+        agent.queues = self.find_queues(agent_id).await;
+        self.store.insert_if_absent(agent_id, &agent).await?;
+
+        Ok(())
+    }
+
+    // 상태 소켓 연결 시 진행 중인 상담 수에 따라 상태를 정한다
+    // 다른 연결에서 직접 자리비움을 골랐다면 그대로 유지하고, 처음 연결하면 대기 상태가 된다
+    pub async fn connect(
+        &self,
+        agent_id: i64,
+        name: String,
+        active_room_ids: HashSet<ChatRoomId>,
+    ) -> MangJooResult<Agent> {
+        let max_concurrent_chats = self.find_max_concurrent_chats(agent_id).await;
+        let mut agent = Agent::new(
+            agent_id.to_string(),
+            name,
+            AgentStatus::Available,
+            active_room_ids,
+            max_concurrent_chats,
+            Utc::now(),
+        );
+        agent.skills = self.find_skills(agent_id).await;
+        agent.queues = self.find_queues(agent_id).await;
+        // 다시 접속해도 배정 순서를 유지하도록 이전 배정, 종료 시각을 이어받는다
+        if let Some(previous) = self.store.get(agent_id).await? {
+            agent.last_assigned_at = previous.last_assigned_at;
+            agent.last_chat_ended_at = previous.last_chat_ended_at;
+            agent.connections = previous.connections;
+            if previous.away_by_choice {
+                agent.status = AgentStatus::Away;
+                agent.away_by_choice = true;
+            }
+        }
+        agent
+            .connections
+            .insert(self.instance_id.clone(), Utc::now());
+        if !agent.has_capacity() && agent.is_available() {
+            agent.update_agent_status(AgentStatus::Busy);
+        }
+
+        self.store.insert(agent_id, &agent).await?;
+        Ok(agent)
+    }
+
+    pub async fn update_max_concurrent_chats(
+        &self,
+        agent_id: i64,
+        max_concurrent_chats: usize,
+    ) -> MangJooResult<()> {
+        self.repository
+            .save_max_concurrent_chats(agent_id, max_concurrent_chats as i32)
+            .await?;
+
+        self.store
+            .update(agent_id, &|agent: &mut Agent| {
+                agent.update_max_concurrent_chats(max_concurrent_chats);
+                Ok(())
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_max_concurrent_chats(&self, agent_id: i64) -> usize {
+        match self.repository.find_max_concurrent_chats(agent_id).await {
+            Ok(Some(max_concurrent_chats)) => max_concurrent_chats as usize,
+            Ok(None) => self.default_max_concurrent_chats,
+            Err(err) => {
+                tracing::error!("Can't load agent capacity {:?}", err);
+                self.default_max_concurrent_chats
+            }
+        }
+    }
+
+    // 기존 스킬은 모두 지우고 새 목록으로 바꾼다
+    pub async fn update_skills(
+        &self,
+        agent_id: i64,
+        skills: HashMap<String, u8>,
+    ) -> MangJooResult<()> {
+        self.repository.replace_skills(agent_id, &skills).await?;
+
+        self.store
+            .update(agent_id, &|agent: &mut Agent| {
+                agent.skills = skills.clone();
+                Ok(())
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_skills(&self, agent_id: i64) -> HashMap<String, u8> {
+        match self.repository.find_skills(agent_id).await {
+            Ok(skills) => skills,
+            Err(err) => {
+                tracing::error!("Can't load agent skills {:?}", err);
+                HashMap::new()
+            }
+        }
+    }
+
+    pub async fn update_queues(&self, agent_id: i64, queues: HashSet<String>) -> MangJooResult<()> {
+        self.repository.replace_queues(agent_id, &queues).await?;
+
+        self.store
+            .update(agent_id, &|agent: &mut Agent| {
+                agent.queues = queues.clone();
+                Ok(())
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_queues(&self, agent_id: i64) -> HashSet<String> {
+        match self.repository.find_queues(agent_id).await {
+            Ok(queues) => queues,
+            Err(err) => {
+                tracing::error!("Can't load agent queues {:?}", err);
+                HashSet::new()
+            }
+        }
+    }
+
+    pub async fn heartbeat(&self, agent_id: i64) {
+        let touched = self
+            .store
+            .update(agent_id, &|agent: &mut Agent| {
+                agent.touch();
+                agent
+                    .connections
+                    .insert(self.instance_id.clone(), Utc::now());
+                Ok(())
+            })
+            .await;
+        if let Err(err) = touched {
+            tracing::error!("Can't update agent heartbeat {:?}", err);
+        }
+    }
+
+    // 일정 시간 활동이 없는 대기 상담원을 자리비움으로 변경
+    pub async fn mark_inactive_away(&self, timeout: chrono::Duration) -> MangJooResult<Vec<i64>> {
+        let now = Utc::now();
+        let is_inactive = |agent: &Agent| agent.is_available() && now - agent.last_active > timeout;
+
+        let mut away_agents = Vec::new();
+        for (agent_id, agent) in self.store.list().await? {
+            if !is_inactive(&agent) {
+                continue;
+            }
+
+            // 목록 조회 이후 하트비트가 들어왔을 수 있으므로 갱신 시점에 다시 확인한다
+            let updated = self
+                .store
+                .update(agent_id, &|agent: &mut Agent| {
+                    if !is_inactive(agent) {
+                        return Err(AppError::InvalidRequest("Agent is active".to_string()));
+                    }
+                    agent.status = AgentStatus::Away;
+                    Ok(())
+                })
+                .await;
+            if let Ok(Some(_)) = updated {
+                away_agents.push(agent_id);
+            }
+        }
+
+        Ok(away_agents)
+    }
+
+    // 어느 인스턴스에도 살아 있는 상태 소켓이 없는 경우에만 목록에서 제거
+    // stale_after 동안 하트비트가 없는 다른 인스턴스의 연결은 끊긴 것으로 본다
+    pub async fn remove_if_disconnected(
+        &self,
+        agent_id: i64,
+        stale_after: chrono::Duration,
+    ) -> MangJooResult<bool> {
+        let mut notifiers = self.notifiers.write().await;
+        let connected = notifiers
+            .get(&agent_id)
+            .is_some_and(|sender| !sender.is_closed());
+        if connected {
+            return Ok(false);
+        }
+        notifiers.remove(&agent_id);
+
+        self.store
+            .update(agent_id, &|agent: &mut Agent| {
+                agent.connections.remove(&self.instance_id);
+                Ok(())
+            })
+            .await?;
+        let since = Utc::now() - stale_after;
+        let removed = self
+            .store
+            .remove_if(agent_id, &|agent: &Agent| !agent.has_live_connection(since))
+            .await?;
+
+        Ok(removed.is_some())
+    }
+
+    pub async fn find_agent(&self, agent_id: i64) -> MangJooResult<Option<Agent>> {
+        self.store.get(agent_id).await
+    }
+
+    pub async fn find_all(&self) -> MangJooResult<Vec<Agent>> {
+        let mut agents: Vec<Agent> = self
+            .store
+            .list()
+            .await?
+            .into_iter()
+            .map(|(_, agent)| agent)
+            .collect();
+        agents.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(agents)
+    }
+
+    // 새 상담을 받을 수 있는 (대기 상태이고 여유가 있는) 상담원
+    pub async fn find_assignable_agents(&self) -> MangJooResult<Vec<(i64, Agent)>> {
+        let agents = self.store.list().await?;
+        Ok(agents
+            .into_iter()
+            .filter(|(_, agent)| agent.is_available() && agent.has_capacity())
+            .collect())
+    }
+
+    // 대기열을 맡는 접속 중인 상담원 (상태와 관계없이)
+    pub async fn find_queue_agents(&self, queue: &str) -> MangJooResult<Vec<i64>> {
+        let agents = self.store.list().await?;
+        Ok(agents
+            .into_iter()
+            .filter(|(_, agent)| agent.serves_queue(queue))
+            .map(|(agent_id, _)| agent_id)
+            .collect())
+    }
+
+    // 대기열을 맡는 대기 상태 상담원 수 (상담중, 자리비움 제외)
+    pub async fn count_available_agents(&self, queue: &str) -> MangJooResult<usize> {
+        let agents = self.store.list().await?;
+        Ok(agents
             .iter()
-            .find(|(_, agent)| agent.status == AgentStatus::Available)
-            .map(|(agent_id, _)| agent_id.clone())
+            .filter(|(_, agent)| agent.is_available() && agent.serves_queue(queue))
+            .count())
     }
 
     pub async fn update_agent_status(
@@ -78,12 +481,241 @@ impl Agents {
         agent_id: i64,
         status: AgentStatus,
     ) -> MangJooResult<i64> {
-        let mut agents = self.agents.write().await;
-        if let Some(agent) = agents.get_mut(&agent_id) {
-            agent.update_agent_status(status);
-            Ok(agent_id)
-        } else {
-            Err(AppError::InvalidRequest("agent update Failed".to_string()))
+        let updated = self
+            .store
+            .update(agent_id, &|agent: &mut Agent| {
+                agent.update_agent_status(status.clone());
+                agent.away_by_choice = status == AgentStatus::Away;
+                Ok(())
+            })
+            .await?;
+
+        match updated {
+            Some(_) => Ok(agent_id),
+            None => Err(AppError::InvalidRequest("agent update Failed".to_string())),
         }
     }
+
+    // 상담원이 직접 방을 맡은 경우 (최대 상담 수와 관계없이 배정)
+    pub async fn assign_room(&self, agent_id: i64, room_id: ChatRoomId) -> MangJooResult<()> {
+        self.store
+            .update(agent_id, &|agent: &mut Agent| {
+                agent.assign_room(room_id.clone());
+                Ok(())
+            })
+            .await?
+            .ok_or_else(|| AppError::InvalidRequest("Not found agent".to_string()))?;
+
+        Ok(())
+    }
+
+    // 자동 배정: 다른 인스턴스가 먼저 채웠다면 실패한다
+    pub async fn try_assign_room(&self, agent_id: i64, room_id: ChatRoomId) -> MangJooResult<()> {
+        self.store
+            .update(agent_id, &|agent: &mut Agent| {
+                if !agent.is_available() || !agent.has_capacity() {
+                    return Err(AppError::InvalidRequest(
+                        "Agent is not available".to_string(),
+                    ));
+                }
+                agent.assign_room(room_id.clone());
+                Ok(())
+            })
+            .await?
+            .ok_or_else(|| AppError::InvalidRequest("Not found agent".to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn release_room(&self, agent_id: i64, room_id: &ChatRoomId) {
+        let released = self
+            .store
+            .update(agent_id, &|agent: &mut Agent| {
+                agent.release_room(room_id);
+                Ok(())
+            })
+            .await;
+        if let Err(err) = released {
+            tracing::error!("Can't release agent room {:?}", err);
+        }
+    }
+
+    // 상담원 알림 채널 구독 (이전 구독은 대체된다)
+    pub async fn subscribe(&self, agent_id: i64) -> mpsc::UnboundedReceiver<AgentNotification> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.notifiers.write().await.insert(agent_id, sender);
+        receiver
+    }
+
+    // 이 인스턴스에 연결된 상태 소켓으로만 전달한다 (인스턴스 간 전달은 RoomBus)
+    pub async fn notify(&self, agent_id: i64, notification: AgentNotification) -> bool {
+        let notifiers = self.notifiers.read().await;
+        match notifiers.get(&agent_id) {
+            Some(sender) => sender.send(notification).is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::test_support::unreachable_pool;
+
+    use super::{super::agent_store::InMemoryAgentStore, *};
+
+    const AGENT_ID: i64 = 7;
+
+    fn room_id(room_id: &str) -> ChatRoomId {
+        serde_json::from_value(room_id.into()).unwrap()
+    }
+
+    fn available_agent(max_concurrent_chats: usize) -> Agent {
+        Agent::new(
+            AGENT_ID.to_string(),
+            "agent".to_string(),
+            AgentStatus::Available,
+            HashSet::new(),
+            max_concurrent_chats,
+            Utc::now(),
+        )
+    }
+
+    // 같은 저장소를 쓰는 두 인스턴스
+    fn instances() -> (Arc<dyn AgentStore>, Agents, Agents) {
+        let store: Arc<dyn AgentStore> = Arc::new(InMemoryAgentStore::new());
+        let agents = || Agents::new(store.clone(), AgentRepository::new(unreachable_pool()), 3);
+        (store.clone(), agents(), agents())
+    }
+
+    async fn connect(agents: &Agents) {
+        agents
+            .connect(AGENT_ID, "agent".to_string(), HashSet::new())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn agent_stays_while_another_instance_is_connected() {
+        let (store, first, second) = instances();
+        connect(&first).await;
+        connect(&second).await;
+        let stale_after = chrono::Duration::seconds(60);
+
+        assert!(!first
+            .remove_if_disconnected(AGENT_ID, stale_after)
+            .await
+            .unwrap());
+        assert!(store.get(AGENT_ID).await.unwrap().is_some());
+
+        assert!(second
+            .remove_if_disconnected(AGENT_ID, stale_after)
+            .await
+            .unwrap());
+        assert!(store.get(AGENT_ID).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn stale_connection_of_another_instance_is_ignored() {
+        let (store, first, second) = instances();
+        connect(&first).await;
+        connect(&second).await;
+
+        // 두 번째 인스턴스가 하트비트 없이 멈춘 경우
+        assert!(first
+            .remove_if_disconnected(AGENT_ID, chrono::Duration::zero())
+            .await
+            .unwrap());
+        assert!(store.get(AGENT_ID).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn agent_stays_while_local_socket_is_open() {
+        let (store, first, _) = instances();
+        let _notifications = first.subscribe(AGENT_ID).await;
+        connect(&first).await;
+
+        assert!(!first
+            .remove_if_disconnected(AGENT_ID, chrono::Duration::zero())
+            .await
+            .unwrap());
+        assert!(store.get(AGENT_ID).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn chosen_away_status_is_kept_on_another_connection() {
+        let (_, first, second) = instances();
+        connect(&first).await;
+        first
+            .update_agent_status(AGENT_ID, AgentStatus::Away)
+            .await
+            .unwrap();
+
+        let agent = second
+            .connect(AGENT_ID, "agent".to_string(), HashSet::new())
+            .await
+            .unwrap();
+
+        assert_eq!(agent.status, AgentStatus::Away);
+    }
+
+    #[tokio::test]
+    async fn first_connection_after_login_is_available() {
+        let (_, first, _) = instances();
+        first.register(AGENT_ID, "agent".to_string()).await.unwrap();
+
+        let agent = first
+            .connect(AGENT_ID, "agent".to_string(), HashSet::new())
+            .await
+            .unwrap();
+
+        assert_eq!(agent.status, AgentStatus::Available);
+    }
+
+    #[test]
+    fn agent_becomes_busy_at_capacity_and_available_after_release() {
+        let mut agent = available_agent(2);
+
+        agent.assign_room(room_id("first"));
+        assert_eq!(agent.status, AgentStatus::Available);
+        agent.assign_room(room_id("second"));
+        assert_eq!(agent.status, AgentStatus::Busy);
+        assert!(!agent.has_capacity());
+
+        agent.release_room(&room_id("first"));
+        assert_eq!(agent.status, AgentStatus::Available);
+        assert!(agent.has_capacity());
+    }
+
+    #[test]
+    fn away_agent_stays_away_when_rooms_change() {
+        let mut agent = available_agent(1);
+        agent.update_agent_status(AgentStatus::Away);
+
+        agent.assign_room(room_id("first"));
+        assert_eq!(agent.status, AgentStatus::Away);
+        agent.release_room(&room_id("first"));
+        assert_eq!(agent.status, AgentStatus::Away);
+    }
+
+    #[test]
+    fn releasing_unknown_room_keeps_status() {
+        let mut agent = available_agent(1);
+        agent.assign_room(room_id("first"));
+
+        agent.release_room(&room_id("other"));
+
+        assert_eq!(agent.status, AgentStatus::Busy);
+        assert!(agent.last_chat_ended_at.is_none());
+    }
+
+    #[test]
+    fn capacity_change_flips_busy_and_available() {
+        let mut agent = available_agent(2);
+        agent.assign_room(room_id("first"));
+
+        agent.update_max_concurrent_chats(1);
+        assert_eq!(agent.status, AgentStatus::Busy);
+        agent.update_max_concurrent_chats(3);
+        assert_eq!(agent.status, AgentStatus::Available);
+    }
 }
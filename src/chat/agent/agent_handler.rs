@@ -0,0 +1,173 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chat::{chatting::chat_service, queue::queue_service},
+    config::{
+        app_state::ArcAppState,
+        error::AppError,
+        session::{RequiredAdmin, RequiredAgent, RequiredStaff},
+        MangJooResult,
+    },
+};
+
+use super::agent::{Agent, AgentStatus};
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAgentStatusRequest {
+    status: AgentStatus,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAgentCapacityRequest {
+    max_concurrent_chats: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AgentSkillRequest {
+    skill: String,
+    proficiency: u8,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAgentSkillsRequest {
+    skills: Vec<AgentSkillRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAgentQueuesRequest {
+    queues: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AgentsResponse {
+    agents: Vec<Agent>,
+}
+
+#[tracing::instrument]
+pub async fn update_agent_status(
+    State(app_state): State<ArcAppState>,
+    RequiredAgent(session): RequiredAgent,
+    Json(request): Json<UpdateAgentStatusRequest>,
+) -> MangJooResult<Json<Agent>> {
+    // 서버 재시작 등으로 목록에 없는 상담원은 다시 등록한다
+    app_state
+        .agents
+        .register(session.user_id, session.name().to_string())
+        .await?;
+    app_state
+        .agents
+        .update_agent_status(session.user_id, request.status.clone())
+        .await?;
+
+    if request.status == AgentStatus::Available {
+        app_state.dispatch_notify.notify_one();
+    }
+
+    find_agent(&app_state, session.user_id).await.map(Json)
+}
+
+#[tracing::instrument]
+pub async fn find_me(
+    State(app_state): State<ArcAppState>,
+    RequiredAgent(session): RequiredAgent,
+) -> MangJooResult<Json<Agent>> {
+    find_agent(&app_state, session.user_id).await.map(Json)
+}
+
+#[tracing::instrument]
+pub async fn find_agents(
+    State(app_state): State<ArcAppState>,
+    RequiredStaff(_session): RequiredStaff,
+) -> MangJooResult<Json<AgentsResponse>> {
+    let agents = app_state.agents.find_all().await?;
+
+    Ok(Json(AgentsResponse { agents }))
+}
+
+const MAX_CONCURRENT_CHATS_LIMIT: usize = 20;
+
+#[tracing::instrument]
+pub async fn update_agent_capacity(
+    State(app_state): State<ArcAppState>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Path(agent_id): Path<i64>,
+    Json(request): Json<UpdateAgentCapacityRequest>,
+) -> MangJooResult<()> {
+    if !(1..=MAX_CONCURRENT_CHATS_LIMIT).contains(&request.max_concurrent_chats) {
+        return Err(AppError::InvalidRequest(format!(
+            "max_concurrent_chats must be between 1 and {}",
+            MAX_CONCURRENT_CHATS_LIMIT
+        )));
+    }
+
+    app_state
+        .agents
+        .update_max_concurrent_chats(agent_id, request.max_concurrent_chats)
+        .await?;
+    app_state.dispatch_notify.notify_one();
+
+    Ok(())
+}
+
+const MAX_PROFICIENCY: u8 = 5;
+
+// 스킬 이름은 방의 상담 주제나 언어와 같은 값을 쓴다 (예: billing, ko)
+#[tracing::instrument]
+pub async fn update_agent_skills(
+    State(app_state): State<ArcAppState>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Path(agent_id): Path<i64>,
+    Json(request): Json<UpdateAgentSkillsRequest>,
+) -> MangJooResult<()> {
+    let mut skills = HashMap::new();
+    for AgentSkillRequest { skill, proficiency } in request.skills {
+        if !(1..=MAX_PROFICIENCY).contains(&proficiency) {
+            return Err(AppError::InvalidRequest(format!(
+                "proficiency must be between 1 and {}",
+                MAX_PROFICIENCY
+            )));
+        }
+        let skill = chat_service::normalize_skill("skill", Some(skill))?
+            .ok_or_else(|| AppError::InvalidRequest("skill is empty".to_string()))?;
+        skills.insert(skill, proficiency);
+    }
+
+    app_state.agents.update_skills(agent_id, skills).await?;
+    app_state.dispatch_notify.notify_one();
+
+    Ok(())
+}
+
+// 빈 목록이면 모든 대기열의 방을 받는다
+#[tracing::instrument]
+pub async fn update_agent_queues(
+    State(app_state): State<ArcAppState>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Path(agent_id): Path<i64>,
+    Json(request): Json<UpdateAgentQueuesRequest>,
+) -> MangJooResult<()> {
+    let mut queues = HashSet::new();
+    for queue in request.queues.iter() {
+        let queue = queue_service::find_queue(queue, &app_state.chat_queues).await?;
+        queues.insert(queue.name);
+    }
+
+    app_state.agents.update_queues(agent_id, queues).await?;
+    app_state.dispatch_notify.notify_one();
+
+    Ok(())
+}
+
+async fn find_agent(app_state: &ArcAppState, agent_id: i64) -> MangJooResult<Agent> {
+    app_state
+        .agents
+        .find_agent(agent_id)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest("Agent is not logged in".to_string()))
+}
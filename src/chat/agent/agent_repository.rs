@@ -0,0 +1,157 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::PgPool;
+
+use crate::config::{error::AppError, MangJooResult};
+
+#[derive(Debug, Clone)]
+pub struct AgentRepository {
+    pool: PgPool,
+}
+
+impl AgentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_max_concurrent_chats(&self, agent_id: i64) -> MangJooResult<Option<i32>> {
+        let max_concurrent_chats = sqlx::query_scalar!(
+            "SELECT max_concurrent_chats
+            FROM agent_profiles
+            WHERE agent_id = $1
+            ",
+            agent_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(max_concurrent_chats)
+    }
+
+    pub async fn save_max_concurrent_chats(
+        &self,
+        agent_id: i64,
+        max_concurrent_chats: i32,
+    ) -> MangJooResult<()> {
+        sqlx::query!(
+            "INSERT INTO agent_profiles (agent_id, max_concurrent_chats)
+            VALUES ($1, $2)
+            ON CONFLICT (agent_id)
+            DO UPDATE SET max_concurrent_chats = $2, updated_at = NOW()
+            ",
+            agent_id,
+            max_concurrent_chats
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn find_skills(&self, agent_id: i64) -> MangJooResult<HashMap<String, u8>> {
+        let rows = sqlx::query!(
+            "SELECT skill, proficiency
+            FROM agent_skills
+            WHERE agent_id = $1
+            ",
+            agent_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.skill, row.proficiency as u8))
+            .collect())
+    }
+
+    pub async fn replace_skills(
+        &self,
+        agent_id: i64,
+        skills: &HashMap<String, u8>,
+    ) -> MangJooResult<()> {
+        let (names, proficiencies): (Vec<String>, Vec<i16>) = skills
+            .iter()
+            .map(|(skill, proficiency)| (skill.clone(), *proficiency as i16))
+            .unzip();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        sqlx::query!("DELETE FROM agent_skills WHERE agent_id = $1", agent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        sqlx::query!(
+            "INSERT INTO agent_skills (agent_id, skill, proficiency)
+            SELECT $1, skill, proficiency
+            FROM UNNEST($2::VARCHAR[], $3::SMALLINT[]) AS skills (skill, proficiency)
+            ",
+            agent_id,
+            &names,
+            &proficiencies
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        tx.commit()
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn find_queues(&self, agent_id: i64) -> MangJooResult<HashSet<String>> {
+        let queues = sqlx::query_scalar!(
+            "SELECT queue
+            FROM agent_queues
+            WHERE agent_id = $1
+            ",
+            agent_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(queues.into_iter().collect())
+    }
+
+    pub async fn replace_queues(
+        &self,
+        agent_id: i64,
+        queues: &HashSet<String>,
+    ) -> MangJooResult<()> {
+        let queues: Vec<String> = queues.iter().cloned().collect();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        sqlx::query!("DELETE FROM agent_queues WHERE agent_id = $1", agent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        sqlx::query!(
+            "INSERT INTO agent_queues (agent_id, queue)
+            SELECT $1, queue
+            FROM UNNEST($2::VARCHAR[]) AS queues (queue)
+            ",
+            agent_id,
+            &queues
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        tx.commit()
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use tokio::sync::RwLock;
+
+use crate::config::{redis_json::RedisJsonMap, MangJooResult};
+
+use super::agent::Agent;
+
+pub type AgentUpdate<'a> = &'a (dyn Fn(&mut Agent) -> MangJooResult<()> + Send + Sync);
+pub type AgentPredicate<'a> = &'a (dyn Fn(&Agent) -> bool + Send + Sync);
+
+// 접속 중인 상담원 저장소 (단일 인스턴스는 메모리, 다중 인스턴스는 Redis)
+#[async_trait]
+pub trait AgentStore: Send + Sync + std::fmt::Debug {
+    async fn get(&self, agent_id: i64) -> MangJooResult<Option<Agent>>;
+
+    async fn insert(&self, agent_id: i64, agent: &Agent) -> MangJooResult<()>;
+
+    // 이미 있는 상담원이면 덮어쓰지 않고 false 를 돌려준다
+    async fn insert_if_absent(&self, agent_id: i64, agent: &Agent) -> MangJooResult<bool>;
+
+    // 갱신 함수가 에러를 돌려주면 저장하지 않는다
+    async fn update(&self, agent_id: i64, apply: AgentUpdate<'_>) -> MangJooResult<Option<Agent>>;
+
+    async fn remove(&self, agent_id: i64) -> MangJooResult<Option<Agent>>;
+
+    // 조건을 만족할 때만 지운다 (확인과 삭제 사이에 다른 인스턴스가 바꾸지 못한다)
+    async fn remove_if(
+        &self,
+        agent_id: i64,
+        predicate: AgentPredicate<'_>,
+    ) -> MangJooResult<Option<Agent>>;
+
+    async fn list(&self) -> MangJooResult<Vec<(i64, Agent)>>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryAgentStore {
+    agents: RwLock<HashMap<i64, Agent>>,
+}
+
+impl InMemoryAgentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AgentStore for InMemoryAgentStore {
+    async fn get(&self, agent_id: i64) -> MangJooResult<Option<Agent>> {
+        Ok(self.agents.read().await.get(&agent_id).cloned())
+    }
+
+    async fn insert(&self, agent_id: i64, agent: &Agent) -> MangJooResult<()> {
+        self.agents.write().await.insert(agent_id, agent.clone());
+        Ok(())
+    }
+
+    async fn insert_if_absent(&self, agent_id: i64, agent: &Agent) -> MangJooResult<bool> {
+        let mut agents = self.agents.write().await;
+        if agents.contains_key(&agent_id) {
+            return Ok(false);
+        }
+        agents.insert(agent_id, agent.clone());
+        Ok(true)
+    }
+
+    async fn update(&self, agent_id: i64, apply: AgentUpdate<'_>) -> MangJooResult<Option<Agent>> {
+        let mut agents = self.agents.write().await;
+        let Some(agent) = agents.get_mut(&agent_id) else {
+            return Ok(None);
+        };
+
+        let mut updated = agent.clone();
+        apply(&mut updated)?;
+        *agent = updated.clone();
+
+        Ok(Some(updated))
+    }
+
+    async fn remove(&self, agent_id: i64) -> MangJooResult<Option<Agent>> {
+        Ok(self.agents.write().await.remove(&agent_id))
+    }
+
+    async fn remove_if(
+        &self,
+        agent_id: i64,
+        predicate: AgentPredicate<'_>,
+    ) -> MangJooResult<Option<Agent>> {
+        let mut agents = self.agents.write().await;
+        if !agents.get(&agent_id).is_some_and(predicate) {
+            return Ok(None);
+        }
+        Ok(agents.remove(&agent_id))
+    }
+
+    async fn list(&self) -> MangJooResult<Vec<(i64, Agent)>> {
+        let agents = self.agents.read().await;
+        Ok(agents
+            .iter()
+            .map(|(agent_id, agent)| (*agent_id, agent.clone()))
+            .collect())
+    }
+}
+
+const AGENT_KEY_PREFIX: &str = "chat:state:agent:";
+const AGENT_INDEX_KEY: &str = "chat:state:agents";
+
+#[derive(Debug, Clone)]
+pub struct RedisAgentStore {
+    agents: RedisJsonMap,
+}
+
+impl RedisAgentStore {
+    pub fn new(connection: MultiplexedConnection) -> Self {
+        Self {
+            agents: RedisJsonMap::new(connection, AGENT_KEY_PREFIX, AGENT_INDEX_KEY),
+        }
+    }
+}
+
+#[async_trait]
+impl AgentStore for RedisAgentStore {
+    async fn get(&self, agent_id: i64) -> MangJooResult<Option<Agent>> {
+        self.agents.get(&agent_id.to_string()).await
+    }
+
+    async fn insert(&self, agent_id: i64, agent: &Agent) -> MangJooResult<()> {
+        self.agents.insert(&agent_id.to_string(), agent).await
+    }
+
+    async fn insert_if_absent(&self, agent_id: i64, agent: &Agent) -> MangJooResult<bool> {
+        self.agents
+            .insert_if_absent(&agent_id.to_string(), agent)
+            .await
+    }
+
+    async fn update(&self, agent_id: i64, apply: AgentUpdate<'_>) -> MangJooResult<Option<Agent>> {
+        self.agents.update(&agent_id.to_string(), apply).await
+    }
+
+    async fn remove(&self, agent_id: i64) -> MangJooResult<Option<Agent>> {
+        self.agents.remove(&agent_id.to_string()).await
+    }
+
+    async fn remove_if(
+        &self,
+        agent_id: i64,
+        predicate: AgentPredicate<'_>,
+    ) -> MangJooResult<Option<Agent>> {
+        self.agents
+            .remove_if(&agent_id.to_string(), predicate)
+            .await
+    }
+
+    // Agent::agent_id 는 사용자 id 문자열이다
+    async fn list(&self) -> MangJooResult<Vec<(i64, Agent)>> {
+        let agents: Vec<Agent> = self.agents.values().await?;
+        Ok(agents
+            .into_iter()
+            .filter_map(|agent| {
+                let agent_id = agent.agent_id.parse().ok()?;
+                Some((agent_id, agent))
+            })
+            .collect())
+    }
+}
@@ -1 +1,6 @@
 pub mod agent;
+pub mod agent_handler;
+pub mod agent_repository;
+pub mod agent_store;
+pub mod presence;
+pub mod routing_strategy;
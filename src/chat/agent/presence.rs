@@ -0,0 +1,175 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    extract::{ws::Message, State, WebSocketUpgrade},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+
+use crate::{
+    chat::offline::offline_service,
+    config::{
+        app_state::ArcAppState,
+        session::{RequiredAgent, UserSession},
+    },
+};
+
+use super::agent::{AgentNotification, AgentStatus};
+
+// 하트비트가 이 시간 이상 없으면 자리비움 처리
+const AWAY_TIMEOUT_SECS: i64 = 60;
+const PRESENCE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+// 상태 소켓 클라이언트 -> 서버 이벤트
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum PresenceEvent {
+    Heartbeat,
+}
+
+#[tracing::instrument]
+pub async fn agent_presence(
+    State(app_state): State<ArcAppState>,
+    ws: WebSocketUpgrade,
+    RequiredAgent(user_session): RequiredAgent,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| {
+        handle_presence_socket(socket, Arc::clone(&app_state), user_session)
+    })
+}
+
+async fn handle_presence_socket(
+    socket: axum::extract::ws::WebSocket,
+    state: ArcAppState,
+    user_session: UserSession,
+) {
+    let agent_id = user_session.user_id;
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+
+    let mut notifications = state.agents.subscribe(agent_id).await;
+    let active_room_ids = state.rooms.find_active_rooms_by_agent(agent_id).await;
+    let agent = match state
+        .agents
+        .connect(agent_id, user_session.name().to_string(), active_room_ids)
+        .await
+    {
+        Ok(agent) => agent,
+        Err(err) => {
+            tracing::error!("Can't connect agent {} {:?}", agent_id, err);
+            return;
+        }
+    };
+    tracing::info!(
+        "Agent {} connected with status {:?}",
+        agent_id,
+        agent.status
+    );
+    state.dispatch_notify.notify_one();
+    notify_pending_messages(&state, agent_id).await;
+
+    let mut send_task = tokio::spawn(async move {
+        while let Some(notification) = notifications.recv().await {
+            let json = match serde_json::to_string(&notification) {
+                Ok(json) => json,
+                Err(err) => {
+                    tracing::error!("Can't serialize agent notification {:?}", err);
+                    continue;
+                }
+            };
+
+            if ws_sender.send(Message::Text(json.into())).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    let agents = state.agents.clone();
+    let mut receive_task = tokio::spawn(async move {
+        while let Some(Ok(message)) = ws_receiver.next().await {
+            let Message::Text(text) = message else {
+                continue;
+            };
+
+            match serde_json::from_str::<PresenceEvent>(text.as_str()) {
+                Ok(PresenceEvent::Heartbeat) => agents.heartbeat(agent_id).await,
+                Err(err) => tracing::warn!("Invalid presence event {:?}", err),
+            }
+        }
+    });
+
+    tokio::select! {
+        _ = &mut send_task => receive_task.abort(),
+        _ = &mut receive_task => send_task.abort(),
+    };
+    // 알림 수신 채널이 닫힌 뒤에 연결 여부를 판단한다
+    let _ = send_task.await;
+
+    match state
+        .agents
+        .remove_if_disconnected(agent_id, chrono::Duration::seconds(AWAY_TIMEOUT_SECS))
+        .await
+    {
+        Ok(true) => tracing::info!("Agent {} disconnected", agent_id),
+        Ok(false) => {}
+        Err(err) => tracing::error!("Can't remove agent {} {:?}", agent_id, err),
+    }
+}
+
+// 영업 시간 밖에 남은 문의가 있으면 접속한 상담원에게 알린다
+async fn notify_pending_messages(state: &ArcAppState, agent_id: i64) {
+    let count = match offline_service::count_pending_messages(
+        agent_id,
+        &state.agents,
+        &state.offline_messages,
+    )
+    .await
+    {
+        Ok(count) => count,
+        Err(err) => {
+            tracing::error!("Can't count offline messages {:?}", err);
+            return;
+        }
+    };
+    if count == 0 {
+        return;
+    }
+
+    let notification = AgentNotification::OfflineMessagesPending {
+        count,
+        notified_at: Utc::now(),
+    };
+    state.agents.notify(agent_id, notification).await;
+}
+
+// 하트비트가 끊긴 상담원을 주기적으로 자리비움 처리한다
+pub fn start_presence_monitor(state: ArcAppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PRESENCE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let away_agents = match state
+                .agents
+                .mark_inactive_away(chrono::Duration::seconds(AWAY_TIMEOUT_SECS))
+                .await
+            {
+                Ok(away_agents) => away_agents,
+                Err(err) => {
+                    tracing::error!("Can't check agent presence {:?}", err);
+                    continue;
+                }
+            };
+            for agent_id in away_agents {
+                tracing::info!("Agent {} is away by inactivity", agent_id);
+                let notification = AgentNotification::StatusChanged {
+                    status: AgentStatus::Away,
+                    changed_at: Utc::now(),
+                };
+                state.notify_agent(agent_id, notification).await;
+            }
+        }
+    })
+}
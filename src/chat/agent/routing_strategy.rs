@@ -0,0 +1,323 @@
+use std::{collections::HashMap, fmt, sync::Arc, sync::Mutex};
+
+use crate::config::{error::AppError, MangJooResult};
+
+use super::agent::Agent;
+
+// 배정 시 참고하는 방 정보
+#[derive(Debug, Clone, Default)]
+pub struct RoutingContext {
+    // 고객이 지난번 상담에서 만난 상담원
+    pub previous_agent_id: Option<i64>,
+}
+
+// 스킬로 걸러진 상담원 중 누구에게 배정할지 정한다
+pub trait RoutingStrategy: Send + Sync + fmt::Debug {
+    fn select(&self, candidates: &[(i64, &Agent)], context: &RoutingContext) -> Option<i64>;
+
+    // 지난 상담원 조회가 필요한 전략만 true
+    fn uses_previous_agent(&self) -> bool {
+        false
+    }
+}
+
+// 상담원 id 순서대로 돌아가며 배정한다 (순번은 인스턴스마다 따로 관리)
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    last_agent_id: Mutex<Option<i64>>,
+}
+
+impl RoutingStrategy for RoundRobin {
+    fn select(&self, candidates: &[(i64, &Agent)], _context: &RoutingContext) -> Option<i64> {
+        let mut last_agent_id = self
+            .last_agent_id
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let next = candidates
+            .iter()
+            .map(|(agent_id, _)| *agent_id)
+            .filter(|agent_id| last_agent_id.is_none_or(|last| *agent_id > last))
+            .min()
+            .or_else(|| candidates.iter().map(|(agent_id, _)| *agent_id).min())?;
+        *last_agent_id = Some(next);
+
+        Some(next)
+    }
+}
+
+// 진행 중인 상담이 가장 적은 상담원
+#[derive(Debug, Default)]
+pub struct LeastBusy;
+
+impl RoutingStrategy for LeastBusy {
+    fn select(&self, candidates: &[(i64, &Agent)], _context: &RoutingContext) -> Option<i64> {
+        candidates
+            .iter()
+            .min_by_key(|(agent_id, agent)| (agent.active_room_ids.len(), *agent_id))
+            .map(|(agent_id, _)| *agent_id)
+    }
+}
+
+// 마지막으로 방을 받거나 상담을 마친 뒤 가장 오래 쉰 상담원 (아직 상담이 없던 상담원이 먼저)
+#[derive(Debug, Default)]
+pub struct LongestIdle;
+
+impl RoutingStrategy for LongestIdle {
+    fn select(&self, candidates: &[(i64, &Agent)], _context: &RoutingContext) -> Option<i64> {
+        candidates
+            .iter()
+            .min_by_key(|(agent_id, agent)| (agent.idle_since(), *agent_id))
+            .map(|(agent_id, _)| *agent_id)
+    }
+}
+
+// 지난번 상담원이 받을 수 있으면 그 상담원, 아니면 가장 한가한 상담원
+#[derive(Debug, Default)]
+pub struct Sticky;
+
+impl RoutingStrategy for Sticky {
+    fn select(&self, candidates: &[(i64, &Agent)], context: &RoutingContext) -> Option<i64> {
+        let previous = context.previous_agent_id.filter(|previous_agent_id| {
+            candidates
+                .iter()
+                .any(|(agent_id, _)| agent_id == previous_agent_id)
+        });
+
+        previous.or_else(|| LeastBusy.select(candidates, context))
+    }
+
+    fn uses_previous_agent(&self) -> bool {
+        true
+    }
+}
+
+fn parse_strategy(name: &str) -> MangJooResult<Arc<dyn RoutingStrategy>> {
+    match name.trim() {
+        "round_robin" => Ok(Arc::new(RoundRobin::default())),
+        "least_busy" => Ok(Arc::new(LeastBusy)),
+        "longest_idle" => Ok(Arc::new(LongestIdle)),
+        "sticky" => Ok(Arc::new(Sticky)),
+        other => Err(AppError::InvalidRequest(format!(
+            "Unknown routing strategy {}",
+            other
+        ))),
+    }
+}
+
+// 대기열별 배정 전략 (설정하지 않은 대기열은 기본 전략)
+#[derive(Debug, Clone)]
+pub struct RoutingStrategies {
+    default: Arc<dyn RoutingStrategy>,
+    queues: HashMap<String, Arc<dyn RoutingStrategy>>,
+}
+
+impl RoutingStrategies {
+    // queues 는 "queue=strategy,queue=strategy" 형식
+    pub fn from_config(default: &str, queues: &str) -> MangJooResult<Self> {
+        let default = parse_strategy(default)?;
+
+        let mut strategies = HashMap::new();
+        for entry in queues.split(',').filter(|entry| !entry.trim().is_empty()) {
+            let (queue, strategy) = entry.split_once('=').ok_or_else(|| {
+                AppError::InvalidRequest(format!("Invalid queue routing strategy {}", entry))
+            })?;
+            strategies.insert(queue.trim().to_string(), parse_strategy(strategy)?);
+        }
+
+        Ok(Self {
+            default,
+            queues: strategies,
+        })
+    }
+
+    pub fn for_queue(&self, queue: &str) -> &dyn RoutingStrategy {
+        self.queues
+            .get(queue)
+            .map(Arc::as_ref)
+            .unwrap_or(self.default.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::{Duration, Utc};
+
+    use crate::{
+        chat::{
+            agent::{
+                agent::{AgentStatus, Agents},
+                agent_repository::AgentRepository,
+                agent_store::{AgentStore, InMemoryAgentStore},
+            },
+            chatting::ChatRoomId,
+        },
+        config::test_support::unreachable_pool,
+    };
+
+    use super::*;
+
+    fn room_id(value: &str) -> ChatRoomId {
+        serde_json::from_value(value.into()).unwrap()
+    }
+
+    // idle_secs 전에 마지막 상담을 마친 상담원 (하트비트는 방금 들어왔다)
+    fn agent(agent_id: i64, active_rooms: usize, idle_secs: i64) -> (i64, Agent) {
+        let active_room_ids: HashSet<ChatRoomId> = (0..active_rooms)
+            .map(|index| room_id(&format!("{}-{}", agent_id, index)))
+            .collect();
+        let mut agent = Agent::new(
+            agent_id.to_string(),
+            format!("agent {}", agent_id),
+            AgentStatus::Available,
+            active_room_ids,
+            5,
+            Utc::now(),
+        );
+        agent.last_chat_ended_at = Some(Utc::now() - Duration::seconds(idle_secs));
+        (agent_id, agent)
+    }
+
+    fn candidates(agents: &[(i64, Agent)]) -> Vec<(i64, &Agent)> {
+        agents
+            .iter()
+            .map(|(agent_id, agent)| (*agent_id, agent))
+            .collect()
+    }
+
+    #[test]
+    fn round_robin_cycles_in_agent_id_order() {
+        let agents = vec![agent(3, 0, 0), agent(1, 0, 0), agent(2, 0, 0)];
+        let strategy = RoundRobin::default();
+        let context = RoutingContext::default();
+
+        let selected: Vec<Option<i64>> = (0..4)
+            .map(|_| strategy.select(&candidates(&agents), &context))
+            .collect();
+
+        assert_eq!(selected, vec![Some(1), Some(2), Some(3), Some(1)]);
+    }
+
+    #[test]
+    fn round_robin_skips_agents_that_are_no_longer_candidates() {
+        let agents = vec![agent(1, 0, 0), agent(2, 0, 0), agent(3, 0, 0)];
+        let strategy = RoundRobin::default();
+        let context = RoutingContext::default();
+
+        assert_eq!(strategy.select(&candidates(&agents), &context), Some(1));
+        let without_two = vec![agent(1, 0, 0), agent(3, 0, 0)];
+        assert_eq!(
+            strategy.select(&candidates(&without_two), &context),
+            Some(3)
+        );
+        assert_eq!(
+            strategy.select(&candidates(&without_two), &context),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn least_busy_prefers_fewest_active_rooms_then_lowest_id() {
+        let agents = vec![agent(1, 2, 0), agent(2, 1, 0), agent(3, 1, 0)];
+
+        let selected = LeastBusy.select(&candidates(&agents), &RoutingContext::default());
+
+        assert_eq!(selected, Some(2));
+    }
+
+    #[test]
+    fn longest_idle_prefers_oldest_chat_end() {
+        let agents = vec![agent(1, 0, 10), agent(2, 3, 300), agent(3, 0, 60)];
+
+        let selected = LongestIdle.select(&candidates(&agents), &RoutingContext::default());
+
+        assert_eq!(selected, Some(2));
+    }
+
+    #[test]
+    fn longest_idle_prefers_agents_without_chats() {
+        let (_, mut fresh) = agent(3, 0, 0);
+        fresh.last_chat_ended_at = None;
+        let agents = vec![agent(1, 0, 300), (3, fresh)];
+
+        let selected = LongestIdle.select(&candidates(&agents), &RoutingContext::default());
+
+        assert_eq!(selected, Some(3));
+    }
+
+    #[tokio::test]
+    async fn longest_idle_ignores_heartbeats() {
+        let store = Arc::new(InMemoryAgentStore::new());
+        for agent_id in [1, 2] {
+            let (_, mut agent) = agent(agent_id, 0, 0);
+            agent.last_chat_ended_at = None;
+            store.insert(agent_id, &agent).await.unwrap();
+        }
+        let agents = Agents::new(store, AgentRepository::new(unreachable_pool()), 5);
+        let select = |agents: Vec<(i64, Agent)>| {
+            LongestIdle.select(&candidates(&agents), &RoutingContext::default())
+        };
+
+        // 1 이 먼저 방을 받았으니 하트비트가 더 최근이어도 더 오래 쉰 상담원이다
+        agents.assign_room(1, room_id("first")).await.unwrap();
+        agents.assign_room(2, room_id("second")).await.unwrap();
+        agents.heartbeat(1).await;
+        assert_eq!(
+            select(agents.find_assignable_agents().await.unwrap()),
+            Some(1)
+        );
+
+        // 상담을 마치면 다시 가장 최근에 일한 상담원이 된다
+        agents.release_room(1, &room_id("first")).await;
+        assert_eq!(
+            select(agents.find_assignable_agents().await.unwrap()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn sticky_prefers_previous_agent_when_available() {
+        let agents = vec![agent(1, 0, 0), agent(2, 3, 0)];
+        let context = RoutingContext {
+            previous_agent_id: Some(2),
+        };
+
+        assert_eq!(Sticky.select(&candidates(&agents), &context), Some(2));
+    }
+
+    #[test]
+    fn sticky_falls_back_to_least_busy() {
+        let agents = vec![agent(1, 2, 0), agent(2, 1, 0)];
+        let context = RoutingContext {
+            previous_agent_id: Some(7),
+        };
+
+        assert_eq!(Sticky.select(&candidates(&agents), &context), Some(2));
+    }
+
+    #[test]
+    fn strategies_return_none_without_candidates() {
+        let context = RoutingContext::default();
+
+        assert_eq!(RoundRobin::default().select(&[], &context), None);
+        assert_eq!(LeastBusy.select(&[], &context), None);
+        assert_eq!(LongestIdle.select(&[], &context), None);
+        assert_eq!(Sticky.select(&[], &context), None);
+    }
+
+    #[test]
+    fn queue_strategies_override_default() {
+        let strategies = RoutingStrategies::from_config("least_busy", "vip=sticky").unwrap();
+
+        assert!(strategies.for_queue("vip").uses_previous_agent());
+        assert!(!strategies.for_queue("default").uses_previous_agent());
+    }
+
+    #[test]
+    fn unknown_strategy_is_rejected() {
+        assert!(RoutingStrategies::from_config("random", "").is_err());
+        assert!(RoutingStrategies::from_config("least_busy", "vip").is_err());
+    }
+}
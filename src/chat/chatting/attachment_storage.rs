@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::config::{error::AppError, MangJooResult};
+
+// 첨부 파일 저장소 (로컬 파일 시스템, 이후 S3 호환 저장소 추가)
+#[async_trait]
+pub trait AttachmentStorage: Send + Sync + std::fmt::Debug {
+    async fn put(&self, storage_key: &str, bytes: Bytes) -> MangJooResult<()>;
+
+    async fn get(&self, storage_key: &str) -> MangJooResult<Bytes>;
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalFileStorage {
+    root: PathBuf,
+}
+
+impl LocalFileStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    // 저장 키는 서버가 만든 "{room_id}/{attachment_id}" 이므로 root 밖을 가리키지 않는다
+    fn path(&self, storage_key: &str) -> PathBuf {
+        self.root.join(storage_key)
+    }
+}
+
+#[async_trait]
+impl AttachmentStorage for LocalFileStorage {
+    async fn put(&self, storage_key: &str, bytes: Bytes) -> MangJooResult<()> {
+        let path = self.path(storage_key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|err| AppError::InternalError(format!("Storage Error {}", err)))?;
+        }
+
+        tokio::fs::write(&path, bytes)
+            .await
+            .map_err(|err| AppError::InternalError(format!("Storage Error {}", err)))
+    }
+
+    async fn get(&self, storage_key: &str) -> MangJooResult<Bytes> {
+        let bytes = tokio::fs::read(self.path(storage_key))
+            .await
+            .map_err(|err| AppError::InternalError(format!("Storage Error {}", err)))?;
+
+        Ok(Bytes::from(bytes))
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::{error::AppError, MangJooResult};
+
+use super::ChatRoomId;
+
+// 첨부 파일 최대 크기 (10MB)
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
+// 업로드된 첨부 파일 (storage_key 는 저장소 내부 경로)
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub attachment_id: String,
+    pub room_id: ChatRoomId,
+    pub uploader_id: i64,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    // 파일 내용으로 형식을 확인하므로 클라이언트가 보낸 Content-Type 은 믿지 않는다
+    pub fn new(
+        room_id: ChatRoomId,
+        uploader_id: i64,
+        file_name: &str,
+        bytes: &[u8],
+    ) -> MangJooResult<Self> {
+        if bytes.is_empty() {
+            return Err(AppError::InvalidRequest("File is empty".to_string()));
+        }
+        if bytes.len() > MAX_ATTACHMENT_BYTES {
+            return Err(AppError::InvalidRequest(format!(
+                "File exceeds {} bytes",
+                MAX_ATTACHMENT_BYTES
+            )));
+        }
+        let content_type = detect_content_type(bytes).ok_or_else(|| {
+            AppError::InvalidRequest(
+                "Only PNG, JPEG, GIF, WEBP images and PDF files are allowed".to_string(),
+            )
+        })?;
+
+        let attachment_id = Uuid::new_v4().to_string();
+        let storage_key = format!("{}/{}", room_id.0, attachment_id);
+
+        Ok(Self {
+            attachment_id,
+            room_id,
+            uploader_id,
+            file_name: sanitize_file_name(file_name),
+            content_type: content_type.to_string(),
+            size_bytes: bytes.len() as i64,
+            storage_key,
+            created_at: Utc::now(),
+        })
+    }
+
+    pub fn info(&self) -> AttachmentInfo {
+        AttachmentInfo {
+            attachment_id: self.attachment_id.clone(),
+            file_name: self.file_name.clone(),
+            content_type: self.content_type.clone(),
+            size_bytes: self.size_bytes,
+        }
+    }
+}
+
+// 채팅 이벤트와 대화 기록에 포함되는 첨부 파일 정보
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentInfo {
+    pub attachment_id: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+}
+
+fn detect_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
+
+// 경로 구분자와 제어 문자를 제거한 파일 이름 (다운로드 헤더에 그대로 쓰인다)
+fn sanitize_file_name(file_name: &str) -> String {
+    let base_name = file_name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let sanitized: String = base_name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(MAX_FILE_NAME_LENGTH)
+        .collect();
+
+    if sanitized.trim().is_empty() {
+        "attachment".to_string()
+    } else {
+        sanitized
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use super::ChatRoomId;
+
+// 팀장 알림 채널에 쌓아둘 수 있는 알림 수 (느린 소켓은 밀린 알림을 건너뛴다)
+const SUPERVISOR_CHANNEL_CAPACITY: usize = 100;
+
+// 연결된 모든 팀장에게 푸시되는 알림
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SupervisorNotification {
+    // 상담원이 방을 이관했다 (to_agent_id 가 없으면 대기열 맨 앞에서 기다린다)
+    RoomEscalated {
+        transfer_id: i64,
+        room_id: ChatRoomId,
+        customer_id: i64,
+        queue: String,
+        from_agent_id: i64,
+        to_agent_id: Option<i64>,
+        note: Option<String>,
+        escalated_at: DateTime<Utc>,
+    },
+    // 다른 팀장이 이관 건을 맡았다
+    EscalationClaimed {
+        transfer_id: i64,
+        room_id: ChatRoomId,
+        claimed_by: i64,
+        claimed_at: DateTime<Utc>,
+    },
+}
+
+// 팀장 알림 소켓은 인스턴스마다 따로 연결되므로 채널은 로컬에 둔다
+#[derive(Debug, Clone)]
+pub struct SupervisorNotifier {
+    sender: broadcast::Sender<SupervisorNotification>,
+}
+
+impl SupervisorNotifier {
+    pub fn new() -> Self {
+        Self {
+            sender: broadcast::channel(SUPERVISOR_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SupervisorNotification> {
+        self.sender.subscribe()
+    }
+
+    // 이 인스턴스에 연결된 팀장에게만 전달한다 (인스턴스 간 전달은 RoomBus)
+    pub fn notify(&self, notification: SupervisorNotification) -> usize {
+        self.sender.send(notification).unwrap_or_default()
+    }
+}
+
+impl Default for SupervisorNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
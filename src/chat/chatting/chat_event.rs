@@ -0,0 +1,527 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{error::AppError, session::UserSession, MangJooResult},
+    user::user::UserRole,
+};
+
+use super::{
+    chat_attachment::AttachmentInfo, chat_message::ChatMessage, chat_room::ChatRoom,
+    queue_status::QueueStatus, ChatRoomId,
+};
+
+const MAX_MESSAGE_LENGTH: usize = 2000;
+const MAX_CLIENT_MESSAGE_ID_LENGTH: usize = 64;
+const MAX_END_REASON_LENGTH: usize = 200;
+
+// 서버 -> 클라이언트 이벤트
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatEvent {
+    Message {
+        message_id: i64,
+        sender_id: i64,
+        role: UserRole,
+        body: String,
+        timestamp: DateTime<Utc>,
+    },
+    Typing {
+        sender_id: i64,
+        role: UserRole,
+        is_typing: bool,
+        timestamp: DateTime<Utc>,
+    },
+    Attachment {
+        message_id: i64,
+        sender_id: i64,
+        role: UserRole,
+        attachment: AttachmentInfo,
+        body: String,
+        timestamp: DateTime<Utc>,
+    },
+    // 보낸 사람에게만 전달되는 저장 완료 응답
+    Ack {
+        client_message_id: Option<String>,
+        message_id: i64,
+        timestamp: DateTime<Utc>,
+    },
+    // sender_id 의 참여자에게 message_id 가 전달됨
+    Delivered {
+        sender_id: i64,
+        role: UserRole,
+        message_id: i64,
+        timestamp: DateTime<Utc>,
+    },
+    // sender_id 의 참여자가 message_id 까지 읽음
+    Read {
+        sender_id: i64,
+        role: UserRole,
+        message_id: i64,
+        timestamp: DateTime<Utc>,
+    },
+    Join {
+        sender_id: i64,
+        role: UserRole,
+        timestamp: DateTime<Utc>,
+    },
+    Leave {
+        sender_id: i64,
+        role: UserRole,
+        timestamp: DateTime<Utc>,
+    },
+    Reconnected {
+        sender_id: i64,
+        role: UserRole,
+        timestamp: DateTime<Utc>,
+    },
+    End {
+        sender_id: i64,
+        role: UserRole,
+        reason: Option<String>,
+        timestamp: DateTime<Utc>,
+    },
+    // 상담 종료 후 고객에게 만족도 응답을 요청한다
+    SurveyRequest {
+        room_id: ChatRoomId,
+        timestamp: DateTime<Utc>,
+    },
+    System {
+        message: String,
+        timestamp: DateTime<Utc>,
+    },
+    // 대기 중인 고객에게 주기적으로 보내는 대기 순번
+    QueuePosition {
+        position: usize,
+        queue_length: usize,
+        estimated_wait_seconds: Option<i64>,
+        timestamp: DateTime<Utc>,
+    },
+    // 팀장과 agent_id 의 상담원만 받는 귓속말 (고객에게는 보내지 않는다)
+    Whisper {
+        sender_id: i64,
+        role: UserRole,
+        agent_id: i64,
+        body: String,
+        timestamp: DateTime<Utc>,
+    },
+    // 방이 다른 상담원이나 대기열로 넘어감 (이전 상담원의 연결은 이 이벤트 뒤에 닫힌다)
+    Transferred {
+        from_agent_id: i64,
+        to_agent_id: Option<i64>,
+        timestamp: DateTime<Utc>,
+    },
+    // 상담원 입장 시 해당 상담원에게만 보내는 고객/대화 정보
+    Context {
+        room_id: ChatRoomId,
+        customer_id: i64,
+        customer_name: String,
+        customer_email: String,
+        topic: Option<String>,
+        language: Option<String>,
+        created_at: DateTime<Utc>,
+        waited_seconds: i64,
+        recent_messages: Vec<ChatMessage>,
+        timestamp: DateTime<Utc>,
+    },
+    Error {
+        code: String,
+        message: String,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl ChatEvent {
+    // 첨부 파일이 있는 메시지는 첨부 이벤트로 보낸다
+    pub fn message(chat_message: &ChatMessage) -> Self {
+        if let Some(attachment) = chat_message.attachment.as_ref() {
+            return ChatEvent::Attachment {
+                message_id: chat_message.message_id,
+                sender_id: chat_message.sender_id,
+                role: chat_message.sender_role.clone(),
+                attachment: attachment.clone(),
+                body: chat_message.body.clone(),
+                timestamp: chat_message.created_at,
+            };
+        }
+
+        ChatEvent::Message {
+            message_id: chat_message.message_id,
+            sender_id: chat_message.sender_id,
+            role: chat_message.sender_role.clone(),
+            body: chat_message.body.clone(),
+            timestamp: chat_message.created_at,
+        }
+    }
+
+    pub fn typing(user_session: &UserSession, is_typing: bool) -> Self {
+        ChatEvent::Typing {
+            sender_id: user_session.user_id,
+            role: user_session.role.clone(),
+            is_typing,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn ack(client_message_id: Option<String>, chat_message: &ChatMessage) -> Self {
+        ChatEvent::Ack {
+            client_message_id,
+            message_id: chat_message.message_id,
+            timestamp: chat_message.created_at,
+        }
+    }
+
+    pub fn delivered(user_session: &UserSession, message_id: i64) -> Self {
+        ChatEvent::Delivered {
+            sender_id: user_session.user_id,
+            role: user_session.role.clone(),
+            message_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn read(user_session: &UserSession, message_id: i64) -> Self {
+        ChatEvent::Read {
+            sender_id: user_session.user_id,
+            role: user_session.role.clone(),
+            message_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn join(user_session: &UserSession) -> Self {
+        ChatEvent::Join {
+            sender_id: user_session.user_id,
+            role: user_session.role.clone(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn leave(user_session: &UserSession) -> Self {
+        ChatEvent::Leave {
+            sender_id: user_session.user_id,
+            role: user_session.role.clone(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn reconnected(user_session: &UserSession) -> Self {
+        ChatEvent::Reconnected {
+            sender_id: user_session.user_id,
+            role: user_session.role.clone(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn end(user_session: &UserSession, reason: Option<String>) -> Self {
+        ChatEvent::End {
+            sender_id: user_session.user_id,
+            role: user_session.role.clone(),
+            reason,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn survey_request(room_id: &ChatRoomId) -> Self {
+        ChatEvent::SurveyRequest {
+            room_id: room_id.clone(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn transferred(from_agent_id: i64, to_agent_id: Option<i64>) -> Self {
+        ChatEvent::Transferred {
+            from_agent_id,
+            to_agent_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn system(message: impl Into<String>) -> Self {
+        ChatEvent::System {
+            message: message.into(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn queue_position(queue_status: &QueueStatus) -> Self {
+        ChatEvent::QueuePosition {
+            position: queue_status.position,
+            queue_length: queue_status.queue_length,
+            estimated_wait_seconds: queue_status.estimated_wait_seconds,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn whisper(user_session: &UserSession, agent_id: i64, body: String) -> Self {
+        ChatEvent::Whisper {
+            sender_id: user_session.user_id,
+            role: user_session.role.clone(),
+            agent_id,
+            body,
+            timestamp: Utc::now(),
+        }
+    }
+
+    // 같은 방 채널을 쓰므로 연결마다 받을 수 있는 이벤트인지 거른다
+    pub fn is_visible_to(&self, user_session: &UserSession) -> bool {
+        match self {
+            ChatEvent::Whisper { agent_id, .. } => {
+                user_session.is_supervisor() || user_session.user_id == *agent_id
+            }
+            // 고객에게는 안내 메시지만 보낸다
+            ChatEvent::Transferred { .. } => !user_session.is_user(),
+            _ => true,
+        }
+    }
+
+    pub fn context(room: &ChatRoom, recent_messages: Vec<ChatMessage>) -> Self {
+        ChatEvent::Context {
+            room_id: room.room_id.clone(),
+            customer_id: room.customer_id,
+            customer_name: room.customer_name.clone(),
+            customer_email: room.customer_email.clone(),
+            topic: room.topic.clone(),
+            language: room.language.clone(),
+            created_at: room.created_at,
+            waited_seconds: room.waited_seconds(),
+            recent_messages,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn error(code: &str, message: impl Into<String>) -> Self {
+        ChatEvent::Error {
+            code: code.to_string(),
+            message: message.into(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn to_json(&self) -> MangJooResult<String> {
+        serde_json::to_string(self)
+            .map_err(|err| AppError::InternalError(format!("Event serialize error {}", err)))
+    }
+}
+
+// 클라이언트 -> 서버 이벤트
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientEvent {
+    Message {
+        body: String,
+        // 클라이언트가 붙이는 임시 id (ack 로 그대로 돌려준다)
+        #[serde(default)]
+        client_message_id: Option<String>,
+    },
+    // 업로드 API 로 먼저 올린 파일을 보낸다 (body 는 설명 문구)
+    Attachment {
+        attachment_id: String,
+        #[serde(default)]
+        body: String,
+        #[serde(default)]
+        client_message_id: Option<String>,
+    },
+    Typing {
+        is_typing: bool,
+    },
+    Read {
+        message_id: i64,
+    },
+    // 팀장과 배정된 상담원 사이의 귓속말
+    Whisper {
+        body: String,
+    },
+    End {
+        #[serde(default)]
+        reason: Option<String>,
+        // 상담원만 남길 수 있다
+        #[serde(default)]
+        summary: Option<String>,
+    },
+}
+
+impl ClientEvent {
+    pub fn parse(text: &str) -> MangJooResult<Self> {
+        let event: ClientEvent = serde_json::from_str(text)
+            .map_err(|err| AppError::InvalidRequest(format!("Invalid event {}", err)))?;
+        event.validate()?;
+
+        Ok(event)
+    }
+
+    fn validate(&self) -> MangJooResult<()> {
+        match self {
+            ClientEvent::Message { body, .. } if body.trim().is_empty() => Err(
+                AppError::InvalidRequest("Message body is empty".to_string()),
+            ),
+            ClientEvent::Message { body, .. } if body.chars().count() > MAX_MESSAGE_LENGTH => {
+                Err(AppError::InvalidRequest(format!(
+                    "Message body exceeds {} characters",
+                    MAX_MESSAGE_LENGTH
+                )))
+            }
+            ClientEvent::Message {
+                client_message_id: Some(client_message_id),
+                ..
+            } if client_message_id.len() > MAX_CLIENT_MESSAGE_ID_LENGTH => {
+                Err(AppError::InvalidRequest(format!(
+                    "client_message_id exceeds {} bytes",
+                    MAX_CLIENT_MESSAGE_ID_LENGTH
+                )))
+            }
+            ClientEvent::Attachment { body, .. } if body.chars().count() > MAX_MESSAGE_LENGTH => {
+                Err(AppError::InvalidRequest(format!(
+                    "Message body exceeds {} characters",
+                    MAX_MESSAGE_LENGTH
+                )))
+            }
+            ClientEvent::Attachment {
+                client_message_id: Some(client_message_id),
+                ..
+            } if client_message_id.len() > MAX_CLIENT_MESSAGE_ID_LENGTH => {
+                Err(AppError::InvalidRequest(format!(
+                    "client_message_id exceeds {} bytes",
+                    MAX_CLIENT_MESSAGE_ID_LENGTH
+                )))
+            }
+            ClientEvent::Whisper { body } if body.trim().is_empty() => Err(
+                AppError::InvalidRequest("Whisper body is empty".to_string()),
+            ),
+            ClientEvent::Whisper { body } if body.chars().count() > MAX_MESSAGE_LENGTH => {
+                Err(AppError::InvalidRequest(format!(
+                    "Whisper body exceeds {} characters",
+                    MAX_MESSAGE_LENGTH
+                )))
+            }
+            ClientEvent::Read { message_id } if *message_id <= 0 => {
+                Err(AppError::InvalidRequest("Invalid message id".to_string()))
+            }
+            ClientEvent::End {
+                reason: Some(reason),
+                ..
+            } if reason.chars().count() > MAX_END_REASON_LENGTH => Err(AppError::InvalidRequest(
+                format!("End reason exceeds {} characters", MAX_END_REASON_LENGTH),
+            )),
+            ClientEvent::End {
+                summary: Some(summary),
+                ..
+            } if summary.chars().count() > MAX_MESSAGE_LENGTH => Err(AppError::InvalidRequest(
+                format!("Summary exceeds {} characters", MAX_MESSAGE_LENGTH),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::test_support::session;
+
+    use super::*;
+
+    fn parse(value: serde_json::Value) -> MangJooResult<ClientEvent> {
+        ClientEvent::parse(&value.to_string())
+    }
+
+    #[test]
+    fn message_is_parsed() {
+        let event = parse(serde_json::json!({ "type": "message", "body": "hello" })).unwrap();
+
+        assert!(matches!(event, ClientEvent::Message { body, .. } if body == "hello"));
+    }
+
+    #[test]
+    fn blank_message_is_rejected() {
+        let event = parse(serde_json::json!({ "type": "message", "body": "  \n" }));
+
+        assert!(matches!(event, Err(AppError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn message_length_is_counted_in_characters() {
+        let longest = "가".repeat(MAX_MESSAGE_LENGTH);
+        let too_long = "가".repeat(MAX_MESSAGE_LENGTH + 1);
+
+        assert!(parse(serde_json::json!({ "type": "message", "body": longest })).is_ok());
+        assert!(matches!(
+            parse(serde_json::json!({ "type": "message", "body": too_long })),
+            Err(AppError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_event_is_rejected() {
+        let event = parse(serde_json::json!({ "type": "shout", "body": "hello" }));
+
+        assert!(matches!(event, Err(AppError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn read_receipt_needs_a_message_id() {
+        assert!(parse(serde_json::json!({ "type": "read", "message_id": 3 })).is_ok());
+        assert!(matches!(
+            parse(serde_json::json!({ "type": "read", "message_id": 0 })),
+            Err(AppError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn client_message_id_is_limited() {
+        let message = |client_message_id: String| {
+            parse(serde_json::json!({
+                "type": "message",
+                "body": "hello",
+                "client_message_id": client_message_id,
+            }))
+        };
+
+        assert!(message("a".repeat(MAX_CLIENT_MESSAGE_ID_LENGTH)).is_ok());
+        assert!(matches!(
+            message("a".repeat(MAX_CLIENT_MESSAGE_ID_LENGTH + 1)),
+            Err(AppError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn end_reason_is_limited() {
+        let end = |reason: String| parse(serde_json::json!({ "type": "end", "reason": reason }));
+
+        assert!(parse(serde_json::json!({ "type": "end" })).is_ok());
+        assert!(end("a".repeat(MAX_END_REASON_LENGTH)).is_ok());
+        assert!(matches!(
+            end("a".repeat(MAX_END_REASON_LENGTH + 1)),
+            Err(AppError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn transfer_event_is_hidden_from_customer() {
+        let transferred = ChatEvent::transferred(7, Some(8));
+
+        assert!(!transferred.is_visible_to(&session(1, UserRole::User)));
+        assert!(transferred.is_visible_to(&session(7, UserRole::Agent)));
+        assert!(transferred.is_visible_to(&session(8, UserRole::Agent)));
+    }
+
+    #[test]
+    fn whisper_is_visible_to_supervisors_and_assigned_agent_only() {
+        let supervisor = session(9, UserRole::Supervisor);
+        let whisper = ChatEvent::whisper(&supervisor, 7, "check the refund policy".to_string());
+
+        assert!(whisper.is_visible_to(&supervisor));
+        assert!(whisper.is_visible_to(&session(10, UserRole::Supervisor)));
+        assert!(whisper.is_visible_to(&session(7, UserRole::Agent)));
+        assert!(!whisper.is_visible_to(&session(8, UserRole::Agent)));
+        assert!(!whisper.is_visible_to(&session(1, UserRole::User)));
+    }
+
+    #[test]
+    fn whisper_body_must_not_be_blank() {
+        assert!(parse(serde_json::json!({ "type": "whisper", "body": "psst" })).is_ok());
+        assert!(matches!(
+            parse(serde_json::json!({ "type": "whisper", "body": " " })),
+            Err(AppError::InvalidRequest(_))
+        ));
+    }
+}
@@ -1,41 +1,106 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use futures::{SinkExt, StreamExt, TryFutureExt};
-use std::{ops::Not, sync::Arc};
-use tokio::sync::broadcast;
+use std::{ops::Not, sync::Arc, time::Duration};
+use tokio::sync::{broadcast, mpsc};
 
 use axum::{
-    extract::{ws::Message, Path, State, WebSocketUpgrade},
-    response::IntoResponse,
+    extract::{ws::Message, Multipart, Path, Query, State, WebSocketUpgrade},
+    response::{IntoResponse, Response},
     Json,
 };
-use serde::Serialize;
+use http::header;
+use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use crate::config::{
-    app_state::ArcAppState,
-    error::AppError,
-    session::{AuthUser, RequiredUser, UserSession},
-    MangJooResult,
+use crate::{
+    chat::{agent::agent::AgentNotification, offline::offline_message::OfflineMessage},
+    config::{
+        app_state::ArcAppState,
+        error::AppError,
+        session::{AuthUser, RequiredSupervisor, RequiredUser, UserSession},
+        MangJooResult,
+    },
 };
 
-use super::{chat_service, ChatRoomId};
+use super::{
+    chat_attachment::AttachmentInfo,
+    chat_escalation::SupervisorNotification,
+    chat_event::{ChatEvent, ClientEvent},
+    chat_message::ChatMessage,
+    chat_room::{ChatRoom, CreatedRoom, NewRoom},
+    chat_service,
+    chat_sla::{RoomSla, SlaBreach, SlaMetric},
+    chat_survey::ChatSurvey,
+    chat_transfer::{ChatTransfer, TransferRequest},
+    event_forwarder::EventForwarder,
+    queue_status::QueueStatus,
+    typing::TypingState,
+    ChatRoomId,
+};
+
+// 재접속 시 다시 보내주는 최대 메시지 수
+const REPLAY_MESSAGE_LIMIT: i64 = 500;
+// 상담을 종료한 연결에 남은 이벤트를 보내며 기다리는 최대 시간
+const SEND_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
 
+// mode 로 상담방이 열렸는지 오프라인 문의로 남았는지 구분한다
 #[derive(Debug, Serialize)]
-pub struct CreateRoomResponse {
-    room_id: ChatRoomId,
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum CreateRoomResponse {
+    Online {
+        room_id: ChatRoomId,
+    },
+    Offline {
+        offline_message: OfflineMessage,
+        next_open_at: Option<DateTime<Utc>>,
+    },
 }
 
 #[tracing::instrument]
 pub async fn create_room(
     State(app_state): State<ArcAppState>,
     RequiredUser(session): RequiredUser,
+    body: Bytes,
 ) -> Result<Json<CreateRoomResponse>, AppError> {
-    let result = chat_service::create_room(session.user_id, &app_state.rooms).await;
+    // 본문 없이 요청하면 기본 대기열에 주제와 언어 없이 방을 만든다
+    let new_room: NewRoom = if body.is_empty() {
+        NewRoom::default()
+    } else {
+        serde_json::from_slice(&body)
+            .map_err(|err| AppError::InvalidRequest(format!("Invalid request {}", err)))?
+    };
+    let result = chat_service::create_room(
+        &session,
+        new_room,
+        &app_state.rooms,
+        &app_state.customers,
+        &app_state.chat_queues,
+        &app_state.offline_messages,
+    )
+    .await;
     match result {
-        Ok(room_id) => {
-            let mut socket_room = app_state.socket_rooms.write().await;
-            socket_room.insert(room_id.clone(), broadcast::channel(100).0);
+        Ok(CreatedRoom::Offline {
+            offline_message,
+            next_open_at,
+        }) => {
+            info!(
+                "Queue {} is closed, left offline message {}",
+                offline_message.queue, offline_message.offline_message_id
+            );
+            return Ok(Json(CreateRoomResponse::Offline {
+                offline_message,
+                next_open_at,
+            }));
+        }
+        Ok(CreatedRoom::Online(room_id)) => {
+            {
+                let mut socket_room = app_state.socket_rooms.write().await;
+                socket_room.insert(room_id.clone(), broadcast::channel(100).0);
+            }
+            app_state.enqueue_room(room_id.clone()).await;
             info!("Success Create Chat Room : {}", room_id.0);
-            return Ok(Json(CreateRoomResponse { room_id: room_id }));
+            return Ok(Json(CreateRoomResponse::Online { room_id: room_id }));
         }
         Err(error) => {
             tracing::error!("Can't make create room {:?}", error);
@@ -44,16 +109,407 @@ pub async fn create_room(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct MessagesQuery {
+    before: Option<i64>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MessagesResponse {
+    messages: Vec<ChatMessage>,
+    next_cursor: Option<i64>,
+}
+
+#[tracing::instrument]
+pub async fn find_messages(
+    State(app_state): State<ArcAppState>,
+    Path(room_id): Path<ChatRoomId>,
+    Query(query): Query<MessagesQuery>,
+    AuthUser(user_session): AuthUser,
+) -> MangJooResult<Json<MessagesResponse>> {
+    let page = chat_service::find_messages(
+        &room_id,
+        &user_session,
+        query.before,
+        query.limit,
+        &app_state.rooms,
+        &app_state.chat_messages,
+    )
+    .await?;
+
+    Ok(Json(MessagesResponse {
+        messages: page.messages,
+        next_cursor: page.next_cursor,
+    }))
+}
+
+// multipart 의 "file" 필드로 파일을 올린다
+#[tracing::instrument(skip(multipart))]
+pub async fn upload_attachment(
+    State(app_state): State<ArcAppState>,
+    Path(room_id): Path<ChatRoomId>,
+    AuthUser(user_session): AuthUser,
+    mut multipart: Multipart,
+) -> MangJooResult<Json<AttachmentInfo>> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|err| AppError::InvalidRequest(format!("Invalid multipart {}", err)))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = field.file_name().unwrap_or_default().to_string();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|err| AppError::InvalidRequest(format!("Invalid multipart {}", err)))?;
+
+        let attachment = chat_service::upload_attachment(
+            &room_id,
+            &user_session,
+            &file_name,
+            bytes,
+            &app_state.rooms,
+            &app_state.attachments,
+            app_state.attachment_storage.as_ref(),
+        )
+        .await?;
+        info!("Attachment uploaded : {}", attachment.attachment_id);
+
+        return Ok(Json(attachment.info()));
+    }
+
+    Err(AppError::InvalidRequest(
+        "file field is required".to_string(),
+    ))
+}
+
+#[tracing::instrument]
+pub async fn download_attachment(
+    State(app_state): State<ArcAppState>,
+    Path(attachment_id): Path<String>,
+    AuthUser(user_session): AuthUser,
+) -> MangJooResult<Response> {
+    let (attachment, bytes) = chat_service::download_attachment(
+        &attachment_id,
+        &user_session,
+        &app_state.rooms,
+        &app_state.attachments,
+        app_state.attachment_storage.as_ref(),
+    )
+    .await?;
+
+    let content_disposition = format!("attachment; filename=\"{}\"", attachment.file_name);
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (header::CONTENT_DISPOSITION, content_disposition),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SurveyRequest {
+    rating: i16,
+    comment: Option<String>,
+}
+
+#[tracing::instrument]
+pub async fn submit_survey(
+    State(app_state): State<ArcAppState>,
+    Path(room_id): Path<ChatRoomId>,
+    RequiredUser(session): RequiredUser,
+    Json(request): Json<SurveyRequest>,
+) -> MangJooResult<Json<ChatSurvey>> {
+    chat_service::submit_survey(
+        &room_id,
+        &session,
+        request.rating,
+        request.comment,
+        &app_state.rooms,
+        &app_state.chat_surveys,
+    )
+    .await
+    .map(Json)
+}
+
+#[tracing::instrument]
+pub async fn transfer_room(
+    State(app_state): State<ArcAppState>,
+    Path(room_id): Path<ChatRoomId>,
+    AuthUser(session): AuthUser,
+    Json(request): Json<TransferRequest>,
+) -> MangJooResult<Json<ChatTransfer>> {
+    let transfer = chat_service::transfer_room(
+        &room_id,
+        &session,
+        request,
+        &app_state.rooms,
+        &app_state.agents,
+        &app_state.chat_transfers,
+    )
+    .await?;
+
+    let room = app_state.rooms.find_room(&room_id).await?;
+    let customer_id = room
+        .as_ref()
+        .map(|room| room.customer_id)
+        .unwrap_or_default();
+    if transfer.escalated {
+        let notification = SupervisorNotification::RoomEscalated {
+            transfer_id: transfer.transfer_id,
+            room_id: room_id.clone(),
+            customer_id,
+            queue: room.map(|room| room.queue).unwrap_or_default(),
+            from_agent_id: transfer.from_agent_id,
+            to_agent_id: transfer.to_agent_id,
+            note: transfer.note.clone(),
+            escalated_at: transfer.created_at,
+        };
+        app_state.notify_supervisors(notification).await;
+    }
+
+    let message = match transfer.to_agent_id {
+        Some(to_agent_id) => {
+            let notification = AgentNotification::RoomTransferred {
+                room_id: room_id.clone(),
+                customer_id,
+                from_agent_id: transfer.from_agent_id,
+                note: transfer.note.clone(),
+                escalated: transfer.escalated,
+                transferred_at: transfer.created_at,
+            };
+            app_state.notify_agent(to_agent_id, notification).await;
+            "You are being transferred to another agent"
+        }
+        None => "You are being connected to the next available agent",
+    };
+    // 이전 상담원의 연결은 이 이벤트를 받고 닫힌다
+    app_state
+        .broadcast(
+            &room_id,
+            ChatEvent::transferred(transfer.from_agent_id, transfer.to_agent_id),
+        )
+        .await;
+    app_state
+        .broadcast(&room_id, ChatEvent::system(message))
+        .await;
+    // 이전 상담원의 자리가 비었거나 방이 대기열로 돌아갔다
+    app_state.dispatch_notify.notify_one();
+
+    Ok(Json(transfer))
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransfersResponse {
+    transfers: Vec<ChatTransfer>,
+}
+
+#[tracing::instrument]
+pub async fn find_transfers(
+    State(app_state): State<ArcAppState>,
+    Path(room_id): Path<ChatRoomId>,
+    AuthUser(session): AuthUser,
+) -> MangJooResult<Json<TransfersResponse>> {
+    if !session.is_agent() && !session.is_supervisor() && !session.is_admin() {
+        return Err(AppError::Unauthorized("Only agent".to_string()));
+    }
+    let transfers = app_state.chat_transfers.find_by_room(&room_id).await?;
+
+    Ok(Json(TransfersResponse { transfers }))
+}
+
+// 팀장이 맡을 이관 건 (소켓이 끊겨 있던 동안 이관된 방도 여기서 확인한다)
+const UNCLAIMED_ESCALATION_LIMIT: i64 = 100;
+
+#[tracing::instrument]
+pub async fn find_escalations(
+    State(app_state): State<ArcAppState>,
+    RequiredSupervisor(_session): RequiredSupervisor,
+) -> MangJooResult<Json<TransfersResponse>> {
+    let transfers = app_state
+        .chat_transfers
+        .find_unclaimed_escalations(UNCLAIMED_ESCALATION_LIMIT)
+        .await?;
+
+    Ok(Json(TransfersResponse { transfers }))
+}
+
+#[tracing::instrument]
+pub async fn claim_escalation(
+    State(app_state): State<ArcAppState>,
+    Path(transfer_id): Path<i64>,
+    RequiredSupervisor(session): RequiredSupervisor,
+) -> MangJooResult<Json<ChatTransfer>> {
+    let transfer =
+        chat_service::claim_escalation(transfer_id, session.user_id, &app_state.chat_transfers)
+            .await?;
+    let claimed_at = transfer.claimed_at.unwrap_or_else(Utc::now);
+
+    let notification = SupervisorNotification::EscalationClaimed {
+        transfer_id,
+        room_id: transfer.room_id.clone(),
+        claimed_by: session.user_id,
+        claimed_at,
+    };
+    app_state.notify_supervisors(notification).await;
+
+    Ok(Json(transfer))
+}
+
+// 팀장 알림 소켓 (이관과 맡은 팀장 알림을 받는다)
+#[tracing::instrument]
+pub async fn supervisor_notifications(
+    State(app_state): State<ArcAppState>,
+    ws: WebSocketUpgrade,
+    RequiredSupervisor(session): RequiredSupervisor,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| {
+        handle_supervisor_socket(socket, Arc::clone(&app_state), session.user_id)
+    })
+}
+
+async fn handle_supervisor_socket(
+    socket: axum::extract::ws::WebSocket,
+    state: ArcAppState,
+    supervisor_id: i64,
+) {
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+    let mut notifications = state.supervisors.subscribe();
+    info!("Supervisor {} connected", supervisor_id);
+
+    let mut send_task = tokio::spawn(async move {
+        loop {
+            let notification = match notifications.recv().await {
+                Ok(notification) => notification,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Supervisor notifications skipped {}", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            };
+            let json = match serde_json::to_string(&notification) {
+                Ok(json) => json,
+                Err(err) => {
+                    tracing::error!("Can't serialize supervisor notification {:?}", err);
+                    continue;
+                }
+            };
+
+            if ws_sender.send(Message::Text(json.into())).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    // 받는 메시지는 없고 연결이 끊겼는지만 본다
+    let mut receive_task =
+        tokio::spawn(async move { while let Some(Ok(_)) = ws_receiver.next().await {} });
+
+    tokio::select! {
+        _ = &mut send_task => receive_task.abort(),
+        _ = &mut receive_task => send_task.abort(),
+    };
+    info!("Supervisor {} disconnected", supervisor_id);
+}
+
+// 방의 배정 대기, 첫 응답, 처리 시간과 위반 기록
+#[tracing::instrument]
+pub async fn find_room_sla(
+    State(app_state): State<ArcAppState>,
+    Path(room_id): Path<ChatRoomId>,
+    AuthUser(session): AuthUser,
+) -> MangJooResult<Json<RoomSla>> {
+    if !session.is_agent() && !session.is_supervisor() && !session.is_admin() {
+        return Err(AppError::Unauthorized("Only agent".to_string()));
+    }
+
+    chat_service::find_room_sla(&room_id, &app_state.chat_sla)
+        .await
+        .map(Json)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlaBreachesQuery {
+    queue: Option<String>,
+    metric: Option<SlaMetric>,
+    since: Option<DateTime<Utc>>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlaBreachesResponse {
+    breaches: Vec<SlaBreach>,
+}
+
+// 팀장이 보는 SLA 위반 목록 (최근 위반부터)
+#[tracing::instrument]
+pub async fn find_sla_breaches(
+    State(app_state): State<ArcAppState>,
+    RequiredSupervisor(_session): RequiredSupervisor,
+    Query(query): Query<SlaBreachesQuery>,
+) -> MangJooResult<Json<SlaBreachesResponse>> {
+    let breaches = chat_service::find_sla_breaches(
+        query.queue.as_deref(),
+        query.metric,
+        query.since,
+        query.limit,
+        &app_state.chat_sla,
+    )
+    .await?;
+
+    Ok(Json(SlaBreachesResponse { breaches }))
+}
+
+#[tracing::instrument]
+pub async fn find_queue_status(
+    State(app_state): State<ArcAppState>,
+    Path(room_id): Path<ChatRoomId>,
+    AuthUser(session): AuthUser,
+) -> MangJooResult<Json<QueueStatus>> {
+    chat_service::find_queue_status(&room_id, &session, &app_state.rooms, &app_state.agents)
+        .await
+        .map(Json)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActiveRoomsResponse {
+    rooms: Vec<ChatRoom>,
+}
+
+// 팀장이 모니터링할 진행 중인 방 목록
+#[tracing::instrument]
+pub async fn find_active_rooms(
+    State(app_state): State<ArcAppState>,
+    RequiredSupervisor(_session): RequiredSupervisor,
+) -> MangJooResult<Json<ActiveRoomsResponse>> {
+    let rooms = app_state.rooms.find_active_rooms().await?;
+
+    Ok(Json(ActiveRoomsResponse { rooms }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JoinQuery {
+    // 마지막으로 받은 메시지 (재접속 시 이후 메시지를 다시 받는다)
+    last_message_id: Option<i64>,
+}
+
 #[tracing::instrument]
 pub async fn join_chat_room(
     State(app_state): State<ArcAppState>,
     ws: WebSocketUpgrade,
     Path(room_id): Path<ChatRoomId>,
+    Query(query): Query<JoinQuery>,
     AuthUser(user_session): AuthUser,
 ) -> impl IntoResponse {
     let is_available_room = app_state
         .rooms
-        .is_available_room(&room_id, user_session.user_id)
+        .is_available_room(&room_id, &user_session)
         .await;
     if is_available_room.not() {
         return Err(AppError::RoomNotFound(format!(
@@ -63,8 +519,14 @@ pub async fn join_chat_room(
     }
 
     Ok(ws.on_upgrade(move |socket| {
-        handle_socket(socket, Arc::clone(&app_state), room_id, user_session)
-            .unwrap_or_else(|err| eprintln!("{}", err))
+        handle_socket(
+            socket,
+            Arc::clone(&app_state),
+            room_id,
+            user_session,
+            query.last_message_id,
+        )
+        .unwrap_or_else(|err| eprintln!("{}", err))
     }))
 }
 
@@ -73,62 +535,323 @@ async fn handle_socket(
     state: ArcAppState,
     room_id: ChatRoomId,
     user_session: UserSession,
+    last_message_id: Option<i64>,
 ) -> MangJooResult<()> {
     let (mut ws_sender, mut ws_receiver) = socket.split();
 
     let tx = {
-        if user_session.is_agent() {
-            let _ = state
+        // 대기 중인 방에 상담원이 직접 들어온 경우 (자동 배정된 방은 그대로 입장)
+        if user_session.is_agent()
+            && !state
                 .rooms
-                .enter_room(user_session.role, room_id.clone(), user_session.user_id)
+                .is_assigned_to(&room_id, user_session.user_id)
+                .await
+        {
+            chat_service::assign_agent(&room_id, user_session.user_id, &state.rooms, &state.agents)
                 .await?;
+            state.dequeue_room(&room_id).await;
         };
 
-        let socket_room = state.socket_rooms.read().await; // write 대신 read 사용
-        socket_room.get(&room_id).expect("Room not found").clone()
+        // 다른 인스턴스에서 만들어진 방이면 이 인스턴스의 채널을 새로 연다
+        let mut socket_room = state.socket_rooms.write().await;
+        socket_room
+            .entry(room_id.clone())
+            .or_insert_with(|| broadcast::channel(100).0)
+            .clone()
+    };
+    let rx = tx.subscribe();
+    // 이 연결에만 보내는 이벤트 (에러 응답 등)
+    let (direct_tx, direct_rx) = mpsc::unbounded_channel::<ChatEvent>();
+
+    // 팀장은 참여자로 기록하지 않고 입장/퇴장도 알리지 않는다
+    let is_monitoring = user_session.is_supervisor();
+    let reconnected = if is_monitoring {
+        false
+    } else {
+        state.rooms.connect(&room_id, user_session.user_id).await?
     };
-    let mut rx = tx.subscribe();
 
+    // 상담원과 팀장은 입장하자마자 고객 정보와 최근 대화를 받는다
+    if user_session.is_agent() || is_monitoring {
+        match chat_service::find_room_context(&room_id, &state.rooms, &state.chat_messages).await {
+            Ok(context) => {
+                let _ = direct_tx.send(context);
+            }
+            Err(err) => tracing::error!("Can't load room context {:?}", err),
+        }
+    }
+
+    // 대기 중인 고객은 입장하자마자 대기 순번을 받는다
+    if user_session.is_user() {
+        match chat_service::find_queue_status(&room_id, &user_session, &state.rooms, &state.agents)
+            .await
+        {
+            Ok(queue_status) => {
+                let _ = direct_tx.send(ChatEvent::queue_position(&queue_status));
+            }
+            Err(AppError::InvalidRequest(_)) => {}
+            Err(err) => tracing::error!("Can't load queue status {:?}", err),
+        }
+    }
+
+    // 구독 이후에 조회하므로 놓치는 메시지는 없다 (중복은 message_id 로 거른다)
+    if let Some(last_message_id) = last_message_id {
+        match state
+            .chat_messages
+            .find_after(&room_id, last_message_id, REPLAY_MESSAGE_LIMIT)
+            .await
+        {
+            Ok(messages) => {
+                for message in messages.iter() {
+                    let _ = direct_tx.send(ChatEvent::message(message));
+                }
+            }
+            Err(err) => tracing::error!("Can't replay chat messages {:?}", err),
+        }
+    }
+
+    if !is_monitoring {
+        let joined = if reconnected {
+            ChatEvent::reconnected(&user_session)
+        } else {
+            ChatEvent::join(&user_session)
+        };
+        state.broadcast(&room_id, joined).await;
+    }
+
+    let send_state = Arc::clone(&state);
+    let send_room_id = room_id.clone();
+    let send_session = user_session.clone();
+    let mut forwarder = EventForwarder::new(rx, direct_rx, user_session.clone());
     let mut send_task = tokio::spawn(async move {
         println!("Starting send task"); // 디버그 로그
-        while let Ok(message) = rx.recv().await {
-            if ws_sender.send(message).await.is_err() {
+        while let Some(event) = forwarder.next().await {
+            let json = match event.to_json() {
+                Ok(json) => json,
+                Err(err) => {
+                    tracing::error!("Can't serialize chat event {:?}", err);
+                    continue;
+                }
+            };
+
+            if ws_sender.send(Message::Text(json.into())).await.is_err() {
                 println!("Error sending message, closing send task"); // 에러 로그
                 return;
             }
+
+            // 상대방 메시지가 소켓에 전달되면 전달 완료로 기록한다
+            if let ChatEvent::Message {
+                message_id,
+                sender_id,
+                ..
+            }
+            | ChatEvent::Attachment {
+                message_id,
+                sender_id,
+                ..
+            } = event
+            {
+                if sender_id != send_session.user_id && !send_session.is_supervisor() {
+                    mark_delivered(&send_state, &send_room_id, &send_session, message_id).await;
+                }
+            }
         }
+        let _ = ws_sender.close().await;
     });
 
-    let tx_clone = tx.clone();
+    let receive_state = Arc::clone(&state);
+    let chat_messages = state.chat_messages.clone();
+    let receive_room_id = room_id.clone();
+    let receive_session = user_session.clone();
     let mut receive_task = tokio::spawn(async move {
         println!("Starting receive task"); // 디버그 로그
-        while let Some(Ok(message)) = ws_receiver.next().await {
-            if let Message::Text(text) = message {
-                if text.to_string() == "종료".to_string() {
-                    let _ = tx_clone
-                        .send(Message::Text(text))
-                        .map_err(|err| AppError::InternalError(err.to_string()));
-                    println!("Chat End");
-                    return;
+        let mut typing = TypingState::new();
+        loop {
+            let message = tokio::select! {
+                message = ws_receiver.next() => message,
+                // 입력 중 이벤트가 끊기면 서버가 대신 입력 종료를 알린다
+                _ = tokio::time::sleep_until(typing.expires_at()), if typing.is_typing() => {
+                    if typing.stop().is_some() {
+                        receive_state
+                            .broadcast(&receive_room_id, ChatEvent::typing(&receive_session, false))
+                            .await;
+                    }
+                    continue;
                 }
+            };
+            let Some(Ok(message)) = message else {
+                break;
+            };
 
-                if tx_clone.send(Message::Text(text)).is_err() {
-                    println!("Error broadcasting message, closing receive task"); // 에러 로그
-                    return;
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Binary(_) => {
+                    let _ = direct_tx.send(ChatEvent::error(
+                        "INVALID_FRAME",
+                        "Only text frames are supported",
+                    ));
+                    continue;
                 }
+                _ => continue,
+            };
+
+            let client_event = match ClientEvent::parse(text.as_str()) {
+                Ok(client_event) => client_event,
+                Err(err) => {
+                    let _ = direct_tx.send(ChatEvent::error("INVALID_EVENT", err.to_string()));
+                    continue;
+                }
+            };
+
+            // 팀장은 귓속말만 보낼 수 있다
+            if receive_session.is_supervisor()
+                && !matches!(client_event, ClientEvent::Whisper { .. })
+            {
+                let _ = direct_tx.send(ChatEvent::error(
+                    "READ_ONLY",
+                    "Supervisors can only send whispers",
+                ));
+                continue;
             }
+
+            // 메시지를 보내면 입력 중 상태는 끝난다
+            let is_message = matches!(
+                client_event,
+                ClientEvent::Message { .. } | ClientEvent::Attachment { .. }
+            );
+            if is_message && typing.stop().is_some() {
+                receive_state
+                    .broadcast(&receive_room_id, ChatEvent::typing(&receive_session, false))
+                    .await;
+            }
+
+            let event = match client_event {
+                ClientEvent::Message {
+                    body,
+                    client_message_id,
+                } => {
+                    let saved = save_message(
+                        &receive_state,
+                        &receive_room_id,
+                        &receive_session,
+                        &body,
+                        None,
+                        client_message_id,
+                        &direct_tx,
+                    )
+                    .await;
+                    match saved {
+                        Some(event) => event,
+                        None => continue,
+                    }
+                }
+                ClientEvent::Attachment {
+                    attachment_id,
+                    body,
+                    client_message_id,
+                } => {
+                    let saved = save_message(
+                        &receive_state,
+                        &receive_room_id,
+                        &receive_session,
+                        &body,
+                        Some(&attachment_id),
+                        client_message_id,
+                        &direct_tx,
+                    )
+                    .await;
+                    match saved {
+                        Some(event) => event,
+                        None => continue,
+                    }
+                }
+                // 입력 상태는 저장하지 않고, 바뀌었거나 일정 간격이 지난 경우에만 알린다
+                ClientEvent::Typing { is_typing } => {
+                    match typing.update(is_typing, tokio::time::Instant::now()) {
+                        Some(is_typing) => ChatEvent::typing(&receive_session, is_typing),
+                        None => continue,
+                    }
+                }
+                ClientEvent::Read { message_id } => {
+                    let read = chat_messages
+                        .mark_read(&receive_room_id, receive_session.user_id, message_id)
+                        .await;
+                    match read {
+                        // 이미 읽은 메시지만 있으면 알리지 않는다
+                        Ok(0) => continue,
+                        Ok(_) => ChatEvent::read(&receive_session, message_id),
+                        Err(err) => {
+                            tracing::error!("Can't save read receipt {:?}", err);
+                            continue;
+                        }
+                    }
+                }
+                ClientEvent::Whisper { body } => {
+                    let whisper = chat_service::whisper(
+                        &receive_room_id,
+                        &receive_session,
+                        body,
+                        &receive_state.rooms,
+                    )
+                    .await;
+                    match whisper {
+                        Ok(event) => event,
+                        Err(err) => {
+                            let _ =
+                                direct_tx.send(ChatEvent::error("WHISPER_FAILED", err.to_string()));
+                            continue;
+                        }
+                    }
+                }
+                ClientEvent::End { reason, summary } => {
+                    let ended = chat_service::end_chat(
+                        &receive_room_id,
+                        &receive_session,
+                        reason.clone(),
+                        summary,
+                        &receive_state.rooms,
+                    )
+                    .await;
+                    if let Err(err) = ended {
+                        let _ = direct_tx.send(ChatEvent::error("END_FAILED", err.to_string()));
+                        continue;
+                    }
+
+                    // 종료한 연결은 곧 닫히므로 방 채널을 기다리지 않고 직접 보낸다
+                    let end = ChatEvent::end(&receive_session, reason);
+                    let survey_request = ChatEvent::survey_request(&receive_room_id);
+                    let _ = direct_tx.send(end.clone());
+                    let _ = direct_tx.send(survey_request.clone());
+                    receive_state.broadcast(&receive_room_id, end).await;
+                    receive_state
+                        .broadcast(&receive_room_id, survey_request)
+                        .await;
+                    println!("Chat End");
+                    return true;
+                }
+            };
+
+            receive_state.broadcast(&receive_room_id, event).await;
         }
         println!("Client disconnected, closing receive task"); // 연결 종료 로그
+        false
     });
 
-    tokio::select! {
+    // 상담 종료 요청으로 끝난 경우에만 방을 바로 정리한다
+    let ended = tokio::select! {
         result = &mut send_task => {
             println!("Send task ended: {:?}", result);  // 종료 이유 로그
             receive_task.abort();
+            false
         },
         result = &mut receive_task => {
             println!("Receive task ended: {:?}", result);  // 종료 이유 로그
-            send_task.abort();
+            let ended = matches!(result, Ok(true));
+            // 종료한 연결은 남은 이벤트(종료, 만족도 요청)를 모두 보낸 뒤 닫는다
+            if !ended || tokio::time::timeout(SEND_DRAIN_TIMEOUT, &mut send_task).await.is_err() {
+                send_task.abort();
+            }
+            ended
         }
     };
 
@@ -137,11 +860,123 @@ async fn handle_socket(
 
     if tx.receiver_count() <= 1 {
         let _ = state.socket_rooms.write().await.remove(&room_id);
-        let _ = state.rooms.remove_room(&room_id);
     };
 
-    let _ = tx.send(Message::Text(
-        format!("A user has disconnected from room {}", room_id.0).into(),
-    ));
+    if is_monitoring {
+        return Ok(());
+    }
+
+    state
+        .broadcast(&room_id, ChatEvent::leave(&user_session))
+        .await;
+
+    if ended {
+        state.close_room(&room_id).await;
+        return Ok(());
+    }
+
+    // 모두 나간 방은 유예 시간 동안 재접속을 기다린다
+    let room = state
+        .rooms
+        .disconnect(&room_id, user_session.user_id)
+        .await?;
+    if room.is_some_and(|room| !room.has_connections()) {
+        state.schedule_abandon_check(room_id);
+    }
+
     Ok(())
 }
+
+// 브로드캐스트 전에 대화 기록으로 저장하고, 보낸 사람에게 ack 를 보낸다
+async fn save_message(
+    state: &ArcAppState,
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    body: &str,
+    attachment_id: Option<&str>,
+    client_message_id: Option<String>,
+    direct_tx: &mpsc::UnboundedSender<ChatEvent>,
+) -> Option<ChatEvent> {
+    // 방을 넘긴 상담원은 더 이상 메시지를 보낼 수 없다
+    if user_session.is_agent()
+        && !state
+            .rooms
+            .is_assigned_to(room_id, user_session.user_id)
+            .await
+    {
+        let _ = direct_tx.send(ChatEvent::error(
+            "NOT_ASSIGNED",
+            "The chat room was assigned to another agent",
+        ));
+        return None;
+    }
+
+    if let Some(attachment_id) = attachment_id {
+        let checked = chat_service::check_sendable_attachment(
+            room_id,
+            attachment_id,
+            user_session,
+            &state.attachments,
+        )
+        .await;
+        if let Err(err) = checked {
+            let _ = direct_tx.send(ChatEvent::error("INVALID_ATTACHMENT", err.to_string()));
+            return None;
+        }
+    }
+
+    let saved = state
+        .chat_messages
+        .save(
+            room_id,
+            user_session.user_id,
+            &user_session.role,
+            body,
+            attachment_id,
+        )
+        .await;
+    match saved {
+        Ok(chat_message) => {
+            if user_session.is_agent() {
+                if let Err(err) = state
+                    .chat_sla
+                    .record_first_response(room_id, chat_message.created_at)
+                    .await
+                {
+                    tracing::error!("Can't record first response {:?}", err);
+                }
+            }
+            let _ = direct_tx.send(ChatEvent::ack(client_message_id, &chat_message));
+            Some(ChatEvent::message(&chat_message))
+        }
+        Err(err) => {
+            tracing::error!("Can't save chat message {:?}", err);
+            let _ = direct_tx.send(ChatEvent::error(
+                "MESSAGE_NOT_SAVED",
+                "Message could not be delivered",
+            ));
+            None
+        }
+    }
+}
+
+async fn mark_delivered(
+    state: &ArcAppState,
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    message_id: i64,
+) {
+    match state
+        .chat_messages
+        .mark_delivered(message_id, user_session.user_id)
+        .await
+    {
+        Ok(true) => {
+            state
+                .broadcast(room_id, ChatEvent::delivered(user_session, message_id))
+                .await
+        }
+        Ok(false) => {}
+        Err(err) => tracing::error!("Can't save delivery receipt {:?}", err),
+    }
+}
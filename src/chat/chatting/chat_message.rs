@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::user::user::UserRole;
+
+use super::{chat_attachment::AttachmentInfo, ChatRoomId};
+
+// 채팅 메시지 (대화 기록)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub message_id: i64,
+    pub room_id: ChatRoomId,
+    pub sender_id: i64,
+    pub sender_role: UserRole,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<AttachmentInfo>,
+    // 수신자별 전달/읽음 상태 (대화 기록 조회 시에만 채워진다)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub receipts: Vec<MessageReceipt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageReceipt {
+    pub user_id: i64,
+    pub delivered_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
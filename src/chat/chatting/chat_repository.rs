@@ -0,0 +1,953 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    config::{error::AppError, MangJooResult},
+    user::user::UserRole,
+};
+
+use super::{
+    chat_attachment::{Attachment, AttachmentInfo},
+    chat_message::{ChatMessage, MessageReceipt},
+    chat_room::{ChatEnd, ChatRoom, EndedBy, RoomStatus},
+    chat_sla::{RoomTimeline, SlaBreach, SlaMetric},
+    chat_survey::ChatSurvey,
+    chat_transfer::ChatTransfer,
+    ChatRoomId,
+};
+
+#[derive(Debug, Clone)]
+pub struct ChatRoomRepository {
+    pool: PgPool,
+}
+
+impl ChatRoomRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn save(&self, chat_room: &ChatRoom) -> MangJooResult<()> {
+        sqlx::query!(
+            "INSERT INTO chat_rooms (room_id, customer_id, customer_name, customer_email, topic, language, queue, priority, agent_id, status, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ",
+            chat_room.room_id.0,
+            chat_room.customer_id,
+            chat_room.customer_name,
+            chat_room.customer_email,
+            chat_room.topic,
+            chat_room.language,
+            chat_room.queue,
+            chat_room.priority,
+            chat_room.agent_id,
+            chat_room.status.to_string(),
+            chat_room.created_at,
+            chat_room.updated_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn update(&self, chat_room: &ChatRoom) -> MangJooResult<()> {
+        let end = chat_room.end.as_ref();
+        sqlx::query!(
+            "UPDATE chat_rooms
+            SET agent_id = $2, status = $3, updated_at = $4, assigned_at = $5,
+                first_assigned_at = COALESCE(first_assigned_at, $5),
+                ended_by = $6, ended_by_user_id = $7, end_reason = $8, summary = $9, ended_at = $10
+            WHERE room_id = $1
+            ",
+            chat_room.room_id.0,
+            chat_room.agent_id,
+            chat_room.status.to_string(),
+            chat_room.updated_at,
+            chat_room.assigned_at,
+            end.map(|end| end.ended_by.to_string()),
+            end.and_then(|end| end.ended_by_user_id),
+            end.and_then(|end| end.reason.clone()),
+            end.and_then(|end| end.summary.clone()),
+            end.map(|end| end.ended_at)
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, room_id: &ChatRoomId) -> MangJooResult<Option<ChatRoom>> {
+        let entity = sqlx::query_as!(
+            ChatRoomEntity,
+            "SELECT room_id, customer_id, customer_name, customer_email, topic, language, queue, priority,
+                agent_id, status,
+                created_at, updated_at, assigned_at, first_assigned_at,
+                ended_by, ended_by_user_id, end_reason, summary, ended_at
+            FROM chat_rooms
+            WHERE room_id = $1
+            ",
+            room_id.0
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(ChatRoom::from))
+    }
+
+    pub async fn find_by_customer(&self, customer_id: i64) -> MangJooResult<Vec<ChatRoom>> {
+        let entities = sqlx::query_as!(
+            ChatRoomEntity,
+            "SELECT room_id, customer_id, customer_name, customer_email, topic, language, queue, priority,
+                agent_id, status,
+                created_at, updated_at, assigned_at, first_assigned_at,
+                ended_by, ended_by_user_id, end_reason, summary, ended_at
+            FROM chat_rooms
+            WHERE customer_id = $1
+            ORDER BY created_at DESC
+            ",
+            customer_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(ChatRoom::from).collect())
+    }
+
+    // 종료되지 않은 방 (서버 재시작 시 복구 대상)
+    pub async fn find_not_ended(&self) -> MangJooResult<Vec<ChatRoom>> {
+        let entities = sqlx::query_as!(
+            ChatRoomEntity,
+            "SELECT room_id, customer_id, customer_name, customer_email, topic, language, queue, priority,
+                agent_id, status,
+                created_at, updated_at, assigned_at, first_assigned_at,
+                ended_by, ended_by_user_id, end_reason, summary, ended_at
+            FROM chat_rooms
+            WHERE status <> $1
+            ORDER BY created_at
+            ",
+            RoomStatus::Ended.to_string()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(ChatRoom::from).collect())
+    }
+
+    pub async fn find_previous_agent(
+        &self,
+        customer_id: i64,
+        room_id: &ChatRoomId,
+    ) -> MangJooResult<Option<i64>> {
+        let agent_id = sqlx::query_scalar!(
+            "SELECT agent_id
+            FROM chat_rooms
+            WHERE customer_id = $1 AND room_id <> $2 AND agent_id IS NOT NULL
+            ORDER BY created_at DESC
+            LIMIT 1
+            ",
+            customer_id,
+            room_id.0
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(agent_id.flatten())
+    }
+
+    // 대기열에서 최근 처음 배정된 limit 개 방의 평균 대기 시간 (배정 기록이 없으면 None)
+    // 넘기기와 재대기로 바뀌는 assigned_at 대신 처음 배정된 시각으로 잰다
+    pub async fn average_wait_seconds(
+        &self,
+        queue: &str,
+        limit: i64,
+    ) -> MangJooResult<Option<f64>> {
+        sqlx::query_scalar!(
+            r#"SELECT AVG(EXTRACT(EPOCH FROM (first_assigned_at - created_at)))::FLOAT8 AS "average_wait_seconds"
+            FROM (
+                SELECT created_at, first_assigned_at
+                FROM chat_rooms
+                WHERE queue = $1 AND first_assigned_at IS NOT NULL
+                ORDER BY first_assigned_at DESC
+                LIMIT $2
+            ) recent
+            "#,
+            queue,
+            limit
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))
+    }
+}
+
+#[derive(Debug)]
+pub struct ChatRoomEntity {
+    room_id: String,
+    customer_id: i64,
+    customer_name: String,
+    customer_email: String,
+    topic: Option<String>,
+    language: Option<String>,
+    queue: String,
+    priority: i32,
+    agent_id: Option<i64>,
+    status: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    assigned_at: Option<DateTime<Utc>>,
+    first_assigned_at: Option<DateTime<Utc>>,
+    ended_by: Option<String>,
+    ended_by_user_id: Option<i64>,
+    end_reason: Option<String>,
+    summary: Option<String>,
+    ended_at: Option<DateTime<Utc>>,
+}
+
+impl From<ChatRoomEntity> for ChatRoom {
+    fn from(entity: ChatRoomEntity) -> Self {
+        let end = match (entity.ended_by, entity.ended_at) {
+            (Some(ended_by), Some(ended_at)) => Some(ChatEnd {
+                ended_by: EndedBy::from(ended_by),
+                ended_by_user_id: entity.ended_by_user_id,
+                reason: entity.end_reason,
+                summary: entity.summary,
+                ended_at,
+            }),
+            _ => None,
+        };
+
+        ChatRoom {
+            room_id: ChatRoomId(entity.room_id),
+            customer_id: entity.customer_id,
+            customer_name: entity.customer_name,
+            customer_email: entity.customer_email,
+            topic: entity.topic,
+            language: entity.language,
+            queue: entity.queue,
+            priority: entity.priority,
+            agent_id: entity.agent_id,
+            status: RoomStatus::from(entity.status),
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+            assigned_at: entity.assigned_at,
+            first_assigned_at: entity.first_assigned_at,
+            participants: HashMap::new(),
+            end,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatMessageRepository {
+    pool: PgPool,
+}
+
+impl ChatMessageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn save(
+        &self,
+        room_id: &ChatRoomId,
+        sender_id: i64,
+        sender_role: &UserRole,
+        body: &str,
+        attachment_id: Option<&str>,
+    ) -> MangJooResult<ChatMessage> {
+        let entity = sqlx::query_as!(
+            ChatMessageEntity,
+            r#"WITH saved AS (
+                INSERT INTO chat_messages (room_id, sender_id, sender_role, body, attachment_id)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING message_id, room_id, sender_id, sender_role, body, created_at, attachment_id
+            )
+            SELECT m.message_id AS "message_id!", m.room_id AS "room_id!",
+                m.sender_id AS "sender_id!", m.sender_role AS "sender_role!",
+                m.body AS "body!", m.created_at AS "created_at!",
+                a.attachment_id AS "attachment_id?", a.file_name AS "file_name?",
+                a.content_type AS "content_type?", a.size_bytes AS "size_bytes?"
+            FROM saved m
+            LEFT JOIN chat_attachments a ON a.attachment_id = m.attachment_id
+            "#,
+            room_id.0,
+            sender_id,
+            sender_role.to_string(),
+            body,
+            attachment_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.into())
+    }
+
+    // before 보다 이전 메시지를 최신순으로 limit 개 조회
+    pub async fn find_by_room(
+        &self,
+        room_id: &ChatRoomId,
+        before: Option<i64>,
+        limit: i64,
+    ) -> MangJooResult<Vec<ChatMessage>> {
+        let entities = sqlx::query_as!(
+            ChatMessageEntity,
+            r#"SELECT m.message_id, m.room_id, m.sender_id, m.sender_role, m.body, m.created_at,
+                a.attachment_id AS "attachment_id?", a.file_name AS "file_name?",
+                a.content_type AS "content_type?", a.size_bytes AS "size_bytes?"
+            FROM chat_messages m
+            LEFT JOIN chat_attachments a ON a.attachment_id = m.attachment_id
+            WHERE m.room_id = $1 AND ($2::BIGINT IS NULL OR m.message_id < $2)
+            ORDER BY m.message_id DESC
+            LIMIT $3
+            "#,
+            room_id.0,
+            before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(ChatMessage::from).collect())
+    }
+
+    // 수신자에게 처음 전달된 경우에만 true
+    pub async fn mark_delivered(&self, message_id: i64, user_id: i64) -> MangJooResult<bool> {
+        let result = sqlx::query!(
+            "INSERT INTO message_receipts (message_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT (message_id, user_id) DO NOTHING
+            ",
+            message_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // message_id 까지 상대방이 보낸 메시지를 모두 읽음 처리하고, 새로 읽은 메시지 수를 돌려준다
+    pub async fn mark_read(
+        &self,
+        room_id: &ChatRoomId,
+        user_id: i64,
+        message_id: i64,
+    ) -> MangJooResult<u64> {
+        let result = sqlx::query!(
+            "INSERT INTO message_receipts (message_id, user_id, read_at)
+            SELECT message_id, $2, NOW()
+            FROM chat_messages
+            WHERE room_id = $1 AND message_id <= $3 AND sender_id <> $2
+            ON CONFLICT (message_id, user_id)
+            DO UPDATE SET read_at = NOW()
+            WHERE message_receipts.read_at IS NULL
+            ",
+            room_id.0,
+            user_id,
+            message_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_receipts(
+        &self,
+        message_ids: &[i64],
+    ) -> MangJooResult<Vec<(i64, MessageReceipt)>> {
+        let entities = sqlx::query_as!(
+            MessageReceiptEntity,
+            "SELECT message_id, user_id, delivered_at, read_at
+            FROM message_receipts
+            WHERE message_id = ANY($1)
+            ORDER BY message_id, user_id
+            ",
+            message_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities
+            .into_iter()
+            .map(|entity| {
+                (
+                    entity.message_id,
+                    MessageReceipt {
+                        user_id: entity.user_id,
+                        delivered_at: entity.delivered_at,
+                        read_at: entity.read_at,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    // after 이후 메시지를 오래된 순으로 limit 개 조회 (재접속 시 놓친 메시지)
+    pub async fn find_after(
+        &self,
+        room_id: &ChatRoomId,
+        after: i64,
+        limit: i64,
+    ) -> MangJooResult<Vec<ChatMessage>> {
+        let entities = sqlx::query_as!(
+            ChatMessageEntity,
+            r#"SELECT m.message_id, m.room_id, m.sender_id, m.sender_role, m.body, m.created_at,
+                a.attachment_id AS "attachment_id?", a.file_name AS "file_name?",
+                a.content_type AS "content_type?", a.size_bytes AS "size_bytes?"
+            FROM chat_messages m
+            LEFT JOIN chat_attachments a ON a.attachment_id = m.attachment_id
+            WHERE m.room_id = $1 AND m.message_id > $2
+            ORDER BY m.message_id
+            LIMIT $3
+            "#,
+            room_id.0,
+            after,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(ChatMessage::from).collect())
+    }
+}
+
+#[derive(Debug)]
+pub struct ChatMessageEntity {
+    message_id: i64,
+    room_id: String,
+    sender_id: i64,
+    sender_role: String,
+    body: String,
+    created_at: DateTime<Utc>,
+    attachment_id: Option<String>,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    size_bytes: Option<i64>,
+}
+
+impl From<ChatMessageEntity> for ChatMessage {
+    fn from(entity: ChatMessageEntity) -> Self {
+        let attachment = match (
+            entity.attachment_id,
+            entity.file_name,
+            entity.content_type,
+            entity.size_bytes,
+        ) {
+            (Some(attachment_id), Some(file_name), Some(content_type), Some(size_bytes)) => {
+                Some(AttachmentInfo {
+                    attachment_id,
+                    file_name,
+                    content_type,
+                    size_bytes,
+                })
+            }
+            _ => None,
+        };
+
+        ChatMessage {
+            message_id: entity.message_id,
+            room_id: ChatRoomId(entity.room_id),
+            sender_id: entity.sender_id,
+            sender_role: UserRole::from(entity.sender_role),
+            body: entity.body,
+            created_at: entity.created_at,
+            attachment,
+            receipts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MessageReceiptEntity {
+    message_id: i64,
+    user_id: i64,
+    delivered_at: DateTime<Utc>,
+    read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttachmentRepository {
+    pool: PgPool,
+}
+
+impl AttachmentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn save(&self, attachment: &Attachment) -> MangJooResult<()> {
+        sqlx::query!(
+            "INSERT INTO chat_attachments
+                (attachment_id, room_id, uploader_id, file_name, content_type, size_bytes, storage_key, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ",
+            attachment.attachment_id,
+            attachment.room_id.0,
+            attachment.uploader_id,
+            attachment.file_name,
+            attachment.content_type,
+            attachment.size_bytes,
+            attachment.storage_key,
+            attachment.created_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, attachment_id: &str) -> MangJooResult<Option<Attachment>> {
+        let entity = sqlx::query_as!(
+            AttachmentEntity,
+            "SELECT attachment_id, room_id, uploader_id, file_name, content_type, size_bytes,
+                storage_key, created_at
+            FROM chat_attachments
+            WHERE attachment_id = $1
+            ",
+            attachment_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(Attachment::from))
+    }
+}
+
+#[derive(Debug)]
+pub struct AttachmentEntity {
+    attachment_id: String,
+    room_id: String,
+    uploader_id: i64,
+    file_name: String,
+    content_type: String,
+    size_bytes: i64,
+    storage_key: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<AttachmentEntity> for Attachment {
+    fn from(entity: AttachmentEntity) -> Self {
+        Attachment {
+            attachment_id: entity.attachment_id,
+            room_id: ChatRoomId(entity.room_id),
+            uploader_id: entity.uploader_id,
+            file_name: entity.file_name,
+            content_type: entity.content_type,
+            size_bytes: entity.size_bytes,
+            storage_key: entity.storage_key,
+            created_at: entity.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatSurveyRepository {
+    pool: PgPool,
+}
+
+impl ChatSurveyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // 이미 응답한 방이면 None
+    pub async fn save(
+        &self,
+        room_id: &ChatRoomId,
+        customer_id: i64,
+        rating: i16,
+        comment: Option<&str>,
+    ) -> MangJooResult<Option<ChatSurvey>> {
+        let entity = sqlx::query_as!(
+            ChatSurveyEntity,
+            "INSERT INTO chat_surveys (room_id, customer_id, rating, comment)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (room_id) DO NOTHING
+            RETURNING room_id, customer_id, rating, comment, created_at
+            ",
+            room_id.0,
+            customer_id,
+            rating,
+            comment
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(ChatSurvey::from))
+    }
+}
+
+#[derive(Debug)]
+pub struct ChatSurveyEntity {
+    room_id: String,
+    customer_id: i64,
+    rating: i16,
+    comment: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ChatSurveyEntity> for ChatSurvey {
+    fn from(entity: ChatSurveyEntity) -> Self {
+        ChatSurvey {
+            room_id: ChatRoomId(entity.room_id),
+            customer_id: entity.customer_id,
+            rating: entity.rating,
+            comment: entity.comment,
+            created_at: entity.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatTransferRepository {
+    pool: PgPool,
+}
+
+impl ChatTransferRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn save(
+        &self,
+        room_id: &ChatRoomId,
+        from_agent_id: i64,
+        to_agent_id: Option<i64>,
+        note: Option<&str>,
+        escalated: bool,
+        transferred_by: i64,
+    ) -> MangJooResult<ChatTransfer> {
+        let entity = sqlx::query_as!(
+            ChatTransferEntity,
+            "INSERT INTO chat_room_transfers
+                (room_id, from_agent_id, to_agent_id, note, escalated, transferred_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING transfer_id, room_id, from_agent_id, to_agent_id, note, escalated,
+                transferred_by, created_at, claimed_by, claimed_at
+            ",
+            room_id.0,
+            from_agent_id,
+            to_agent_id,
+            note,
+            escalated,
+            transferred_by
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.into())
+    }
+
+    pub async fn find_by_room(&self, room_id: &ChatRoomId) -> MangJooResult<Vec<ChatTransfer>> {
+        let entities = sqlx::query_as!(
+            ChatTransferEntity,
+            "SELECT transfer_id, room_id, from_agent_id, to_agent_id, note, escalated,
+                transferred_by, created_at, claimed_by, claimed_at
+            FROM chat_room_transfers
+            WHERE room_id = $1
+            ORDER BY transfer_id
+            ",
+            room_id.0
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(ChatTransfer::from).collect())
+    }
+
+    // 아직 아무 팀장도 맡지 않은 이관 건 (먼저 이관된 순)
+    pub async fn find_unclaimed_escalations(&self, limit: i64) -> MangJooResult<Vec<ChatTransfer>> {
+        let entities = sqlx::query_as!(
+            ChatTransferEntity,
+            "SELECT transfer_id, room_id, from_agent_id, to_agent_id, note, escalated,
+                transferred_by, created_at, claimed_by, claimed_at
+            FROM chat_room_transfers
+            WHERE escalated AND claimed_by IS NULL
+            ORDER BY transfer_id
+            LIMIT $1
+            ",
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(ChatTransfer::from).collect())
+    }
+
+    // 맡은 팀장이 없는 이관 건만 맡는다 (동시에 맡으려 하면 한 명만 성공)
+    pub async fn claim(
+        &self,
+        transfer_id: i64,
+        supervisor_id: i64,
+    ) -> MangJooResult<Option<ChatTransfer>> {
+        let entity = sqlx::query_as!(
+            ChatTransferEntity,
+            "UPDATE chat_room_transfers
+            SET claimed_by = $2, claimed_at = NOW()
+            WHERE transfer_id = $1 AND escalated AND claimed_by IS NULL
+            RETURNING transfer_id, room_id, from_agent_id, to_agent_id, note, escalated,
+                transferred_by, created_at, claimed_by, claimed_at
+            ",
+            transfer_id,
+            supervisor_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(ChatTransfer::from))
+    }
+}
+
+#[derive(Debug)]
+pub struct ChatTransferEntity {
+    transfer_id: i64,
+    room_id: String,
+    from_agent_id: i64,
+    to_agent_id: Option<i64>,
+    note: Option<String>,
+    escalated: bool,
+    transferred_by: i64,
+    created_at: DateTime<Utc>,
+    claimed_by: Option<i64>,
+    claimed_at: Option<DateTime<Utc>>,
+}
+
+impl From<ChatTransferEntity> for ChatTransfer {
+    fn from(entity: ChatTransferEntity) -> Self {
+        ChatTransfer {
+            transfer_id: entity.transfer_id,
+            room_id: ChatRoomId(entity.room_id),
+            from_agent_id: entity.from_agent_id,
+            to_agent_id: entity.to_agent_id,
+            note: entity.note,
+            escalated: entity.escalated,
+            transferred_by: entity.transferred_by,
+            created_at: entity.created_at,
+            claimed_by: entity.claimed_by,
+            claimed_at: entity.claimed_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatSlaRepository {
+    pool: PgPool,
+}
+
+impl ChatSlaRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // 방의 첫 상담원 메시지 시각만 남긴다
+    pub async fn record_first_response(
+        &self,
+        room_id: &ChatRoomId,
+        responded_at: DateTime<Utc>,
+    ) -> MangJooResult<()> {
+        sqlx::query!(
+            "UPDATE chat_rooms
+            SET first_response_at = $2
+            WHERE room_id = $1 AND first_response_at IS NULL
+            ",
+            room_id.0,
+            responded_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn find_timeline(&self, room_id: &ChatRoomId) -> MangJooResult<Option<RoomTimeline>> {
+        let entity = sqlx::query_as!(
+            RoomTimelineEntity,
+            "SELECT room_id, queue, agent_id, created_at, first_assigned_at, first_response_at, ended_at
+            FROM chat_rooms
+            WHERE room_id = $1
+            ",
+            room_id.0
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(RoomTimeline::from))
+    }
+
+    // 최근 lookback_secs 안에 만들어진 방 중 목표를 넘긴 지표를 기록하고, 새로 기록된 것만 돌려준다
+    // 아직 끝나지 않은 구간은 지금까지의 시간으로 판단하므로 대기 중인 방도 바로 감지된다
+    pub async fn record_breaches(&self, lookback_secs: i32) -> MangJooResult<Vec<SlaBreach>> {
+        let entities = sqlx::query_as!(
+            SlaBreachEntity,
+            r#"WITH recent AS (
+                SELECT r.room_id, r.queue, r.agent_id, r.created_at, r.first_assigned_at,
+                    r.first_response_at, r.ended_at,
+                    t.time_to_assign_secs, t.first_response_secs, t.handle_time_secs
+                FROM chat_rooms r
+                JOIN queue_sla_targets t ON t.queue = r.queue
+                WHERE r.created_at > NOW() - $1::INT * INTERVAL '1 second'
+            ),
+            breaches AS (
+                SELECT room_id, 'time_to_assign' AS metric, queue, agent_id,
+                    time_to_assign_secs AS target_secs,
+                    created_at + time_to_assign_secs * INTERVAL '1 second' AS breached_at
+                FROM recent
+                WHERE time_to_assign_secs IS NOT NULL
+                  AND COALESCE(first_assigned_at, ended_at, NOW())
+                    > created_at + time_to_assign_secs * INTERVAL '1 second'
+                UNION ALL
+                SELECT room_id, 'first_response', queue, agent_id, first_response_secs,
+                    first_assigned_at + first_response_secs * INTERVAL '1 second'
+                FROM recent
+                WHERE first_response_secs IS NOT NULL
+                  AND first_assigned_at IS NOT NULL
+                  AND COALESCE(first_response_at, ended_at, NOW())
+                    > first_assigned_at + first_response_secs * INTERVAL '1 second'
+                UNION ALL
+                SELECT room_id, 'handle_time', queue, agent_id, handle_time_secs,
+                    first_assigned_at + handle_time_secs * INTERVAL '1 second'
+                FROM recent
+                WHERE handle_time_secs IS NOT NULL
+                  AND first_assigned_at IS NOT NULL
+                  AND COALESCE(ended_at, NOW())
+                    > first_assigned_at + handle_time_secs * INTERVAL '1 second'
+            )
+            INSERT INTO sla_breaches (room_id, metric, queue, agent_id, target_secs, breached_at)
+            SELECT room_id, metric, queue, agent_id, target_secs, breached_at
+            FROM breaches
+            ON CONFLICT (room_id, metric) DO NOTHING
+            RETURNING room_id, metric, queue, agent_id, target_secs, breached_at, detected_at
+            "#,
+            lookback_secs
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(SlaBreach::from).collect())
+    }
+
+    // 최근 위반부터
+    pub async fn find_breaches(
+        &self,
+        queue: Option<&str>,
+        metric: Option<SlaMetric>,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> MangJooResult<Vec<SlaBreach>> {
+        let entities = sqlx::query_as!(
+            SlaBreachEntity,
+            "SELECT room_id, metric, queue, agent_id, target_secs, breached_at, detected_at
+            FROM sla_breaches
+            WHERE ($1::VARCHAR IS NULL OR queue = $1)
+              AND ($2::VARCHAR IS NULL OR metric = $2)
+              AND breached_at >= $3
+            ORDER BY breached_at DESC
+            LIMIT $4
+            ",
+            queue,
+            metric.map(|metric| metric.to_string()),
+            since,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(SlaBreach::from).collect())
+    }
+
+    pub async fn find_breaches_by_room(
+        &self,
+        room_id: &ChatRoomId,
+    ) -> MangJooResult<Vec<SlaBreach>> {
+        let entities = sqlx::query_as!(
+            SlaBreachEntity,
+            "SELECT room_id, metric, queue, agent_id, target_secs, breached_at, detected_at
+            FROM sla_breaches
+            WHERE room_id = $1
+            ORDER BY breached_at
+            ",
+            room_id.0
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(SlaBreach::from).collect())
+    }
+}
+
+#[derive(Debug)]
+pub struct RoomTimelineEntity {
+    room_id: String,
+    queue: String,
+    agent_id: Option<i64>,
+    created_at: DateTime<Utc>,
+    first_assigned_at: Option<DateTime<Utc>>,
+    first_response_at: Option<DateTime<Utc>>,
+    ended_at: Option<DateTime<Utc>>,
+}
+
+impl From<RoomTimelineEntity> for RoomTimeline {
+    fn from(entity: RoomTimelineEntity) -> Self {
+        RoomTimeline {
+            room_id: ChatRoomId(entity.room_id),
+            queue: entity.queue,
+            agent_id: entity.agent_id,
+            created_at: entity.created_at,
+            first_assigned_at: entity.first_assigned_at,
+            first_response_at: entity.first_response_at,
+            ended_at: entity.ended_at,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SlaBreachEntity {
+    room_id: String,
+    metric: String,
+    queue: String,
+    agent_id: Option<i64>,
+    target_secs: i32,
+    breached_at: DateTime<Utc>,
+    detected_at: DateTime<Utc>,
+}
+
+impl From<SlaBreachEntity> for SlaBreach {
+    fn from(entity: SlaBreachEntity) -> Self {
+        SlaBreach {
+            room_id: ChatRoomId(entity.room_id),
+            metric: SlaMetric::from(entity.metric),
+            queue: entity.queue,
+            agent_id: entity.agent_id,
+            target_secs: entity.target_secs,
+            breached_at: entity.breached_at,
+            detected_at: entity.detected_at,
+        }
+    }
+}
@@ -1,124 +1,778 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
-use tokio::sync::RwLock;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    config::{error::AppError, MangJooResult},
+    chat::{offline::offline_message::OfflineMessage, queue::chat_queue::DEFAULT_QUEUE},
+    config::{error::AppError, session::UserSession, MangJooResult},
     user::user::UserRole,
 };
 
-use super::ChatRoomId;
+use super::{
+    chat_repository::ChatRoomRepository,
+    room_store::{queue_score, RoomStore},
+    ChatRoomId,
+};
+
+// 예상 대기 시간 계산에 쓰는 최근 배정 수
+const RECENT_ASSIGNMENT_SAMPLE: i64 = 50;
+// 방 상태를 DB 에 기록할 때 시도하는 횟수와 간격
+const PERSIST_ATTEMPTS: u32 = 3;
+const PERSIST_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+// 연결이 끊긴 뒤 방을 유지하는 시간
+pub const RECONNECT_GRACE_SECS: i64 = 60;
 
 // 채팅방 정보
-#[derive(Debug, Clone, Serialize)]
-struct ChatRoom {
-    room_id: ChatRoomId,
-    customer_id: String,
-    agent_id: Option<String>,
-    status: RoomStatus,
-    created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatRoom {
+    pub room_id: ChatRoomId,
+    pub customer_id: i64,
+    // 방 생성 시점의 고객 정보
+    #[serde(default)]
+    pub customer_name: String,
+    #[serde(default)]
+    pub customer_email: String,
+    // 고객이 고른 상담 주제와 언어 (상담원 스킬과 맞춰 배정한다)
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    // 방이 기다리는 대기열과 그 안에서의 우선순위 (고객 등급)
+    #[serde(default = "default_queue")]
+    pub queue: String,
+    #[serde(default)]
+    pub priority: i32,
+    pub agent_id: Option<i64>,
+    pub status: RoomStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub assigned_at: Option<DateTime<Utc>>,
+    // 처음 배정된 시각 (넘기기와 재대기에도 바뀌지 않는다)
+    #[serde(default)]
+    pub first_assigned_at: Option<DateTime<Utc>>,
+    // 참여자별 소켓 연결 상태 (모든 인스턴스 기준)
+    #[serde(default)]
+    pub participants: HashMap<i64, Participant>,
+    #[serde(default)]
+    pub end: Option<ChatEnd>,
+}
+
+fn default_queue() -> String {
+    DEFAULT_QUEUE.to_string()
+}
+
+// 방을 만들 때 고객이 고르는 값 (주제와 언어는 상담원 스킬, 대기열은 배정 순서에 쓰인다)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NewRoom {
+    pub topic: Option<String>,
+    pub language: Option<String>,
+    pub queue: Option<String>,
+    // 대기열이 영업 시간 밖일 때 남기는 문의
+    pub message: Option<String>,
+}
+
+// 영업 중이면 방을 만들고, 아니면 오프라인 문의를 남긴다
+#[derive(Debug, Clone)]
+pub enum CreatedRoom {
+    Online(ChatRoomId),
+    Offline {
+        offline_message: OfflineMessage,
+        next_open_at: Option<DateTime<Utc>>,
+    },
+}
+
+// 상담 종료 기록
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatEnd {
+    pub ended_by: EndedBy,
+    pub ended_by_user_id: Option<i64>,
+    pub reason: Option<String>,
+    // 상담원이 남기는 상담 요약
+    pub summary: Option<String>,
+    pub ended_at: DateTime<Utc>,
+}
+
+impl ChatEnd {
+    pub fn by_user(
+        user_session: &UserSession,
+        reason: Option<String>,
+        summary: Option<String>,
+    ) -> Self {
+        let ended_by = if user_session.is_agent() {
+            EndedBy::Agent
+        } else {
+            EndedBy::Customer
+        };
+
+        Self {
+            ended_by,
+            ended_by_user_id: Some(user_session.user_id),
+            reason,
+            summary,
+            ended_at: Utc::now(),
+        }
+    }
+
+    pub fn timeout() -> Self {
+        Self {
+            ended_by: EndedBy::Timeout,
+            ended_by_user_id: None,
+            reason: None,
+            summary: None,
+            ended_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EndedBy {
+    Customer,
+    Agent,
+    Timeout, // 참여자가 모두 나간 뒤 재접속하지 않음
+}
+
+impl From<String> for EndedBy {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "customer" => Self::Customer,
+            "agent" => Self::Agent,
+            _ => Self::Timeout,
+        }
+    }
+}
+
+impl fmt::Display for EndedBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndedBy::Customer => write!(f, "customer"),
+            EndedBy::Agent => write!(f, "agent"),
+            EndedBy::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+// 재시작 후 복구한 진행 중인 방 (inserted 가 false 면 이미 저장소에 있던 방)
+#[derive(Debug, Clone)]
+pub struct RestoredRoom {
+    pub room: ChatRoom,
+    pub inserted: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Participant {
+    pub connections: u32,
+    pub disconnected_at: Option<DateTime<Utc>>,
 }
 
 impl ChatRoom {
-    pub fn enter_agent(&mut self, agent_id: String) -> MangJooResult<()> {
+    pub fn enter_agent(&mut self, agent_id: i64) -> MangJooResult<()> {
+        if self.status == RoomStatus::Ended {
+            return Err(AppError::InvalidRequest(
+                "This chat room is ended".to_string(),
+            ));
+        }
         if self.agent_id.is_some() {
             return Err(AppError::InvalidRequest(
                 "This chat room is full".to_string(),
             ));
         }
+        let now = Utc::now();
         self.agent_id = Some(agent_id);
         self.status = RoomStatus::Connected;
+        self.assigned_at = Some(now);
+        self.first_assigned_at.get_or_insert(now);
+        self.updated_at = now;
+
+        Ok(())
+    }
+
+    // 배정된 상담원이 다른 상담원에게 방을 넘긴다
+    pub fn transfer_to_agent(&mut self, from_agent_id: i64, to_agent_id: i64) -> MangJooResult<()> {
+        self.check_transferable(from_agent_id)?;
+        if from_agent_id == to_agent_id {
+            return Err(AppError::InvalidRequest(
+                "Can't transfer to the same agent".to_string(),
+            ));
+        }
+        let now = Utc::now();
+        self.agent_id = Some(to_agent_id);
+        self.assigned_at = Some(now);
+        self.updated_at = now;
+
+        Ok(())
+    }
+
+    // 배정을 해제하고 다시 대기 상태로 만든다
+    pub fn return_to_queue(&mut self, from_agent_id: i64) -> MangJooResult<()> {
+        self.check_transferable(from_agent_id)?;
+        self.agent_id = None;
+        self.status = RoomStatus::Waiting;
+        self.assigned_at = None;
         self.updated_at = Utc::now();
 
         Ok(())
     }
 
-    pub fn end_chat(&mut self) {
+    fn check_transferable(&self, from_agent_id: i64) -> MangJooResult<()> {
+        if self.status != RoomStatus::Connected {
+            return Err(AppError::InvalidRequest(
+                "Only connected chat rooms can be transferred".to_string(),
+            ));
+        }
+        if self.agent_id != Some(from_agent_id) {
+            return Err(AppError::InvalidRequest(
+                "The chat room was assigned to another agent".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    // 배정할 상담원에게 필요한 스킬 (주제, 언어 순)
+    pub fn required_skills(&self) -> Vec<&str> {
+        self.topic
+            .iter()
+            .chain(self.language.iter())
+            .map(String::as_str)
+            .collect()
+    }
+
+    // 상담원 배정까지 기다린 시간 (대기 중이면 지금까지, 아니면 처음 배정될 때까지)
+    pub fn waited_seconds(&self) -> i64 {
+        let until = match self.status {
+            RoomStatus::Waiting => Utc::now(),
+            _ => self.first_assigned_at.unwrap_or_else(Utc::now),
+        };
+        (until - self.created_at).num_seconds().max(0)
+    }
+
+    pub fn connect_participant(&mut self, user_id: i64) -> MangJooResult<()> {
+        if self.status == RoomStatus::Ended {
+            return Err(AppError::InvalidRequest(
+                "This chat room is ended".to_string(),
+            ));
+        }
+        let participant = self.participants.entry(user_id).or_default();
+        participant.connections += 1;
+        participant.disconnected_at = None;
+
+        Ok(())
+    }
+
+    pub fn disconnect_participant(&mut self, user_id: i64) {
+        if let Some(participant) = self.participants.get_mut(&user_id) {
+            participant.connections = participant.connections.saturating_sub(1);
+            if participant.connections == 0 {
+                participant.disconnected_at = Some(Utc::now());
+            }
+        }
+    }
+
+    // 연결이 끊겼다가 다시 들어오는 참여자인지 여부
+    pub fn is_reconnecting(&self, user_id: i64) -> bool {
+        self.participants
+            .get(&user_id)
+            .is_some_and(|participant| participant.connections == 0)
+    }
+
+    pub fn has_connections(&self) -> bool {
+        self.participants
+            .values()
+            .any(|participant| participant.connections > 0)
+    }
+
+    // 재시작 전의 소켓은 모두 끊겼으므로 고객과 상담원을 복구 시점에 끊긴 것으로 본다
+    // (유예 시간 안에 아무도 다시 들어오지 않으면 방이 종료된다)
+    pub fn mark_restored(&mut self, restored_at: DateTime<Utc>) {
+        let disconnected = Participant {
+            connections: 0,
+            disconnected_at: Some(restored_at),
+        };
+        self.participants.clear();
+        self.participants
+            .insert(self.customer_id, disconnected.clone());
+        if let Some(agent_id) = self.agent_id {
+            self.participants.insert(agent_id, disconnected);
+        }
+    }
+
+    // 모든 참여자의 연결이 유예 시간 이상 끊긴 방
+    pub fn is_abandoned(&self, grace_period: chrono::Duration) -> bool {
+        let now = Utc::now();
+        !self.participants.is_empty()
+            && self.participants.values().all(|participant| {
+                participant.connections == 0
+                    && participant
+                        .disconnected_at
+                        .is_some_and(|disconnected_at| now - disconnected_at >= grace_period)
+            })
+    }
+
+    pub fn end_chat(&mut self, end: ChatEnd) -> MangJooResult<()> {
+        if self.status == RoomStatus::Ended {
+            return Err(AppError::InvalidRequest(
+                "This chat room is already ended".to_string(),
+            ));
+        }
         self.status = RoomStatus::Ended;
-        self.updated_at = Utc::now();
+        self.updated_at = end.ended_at;
+        self.end = Some(end);
+
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum RoomStatus {
     Waiting,   // 상담원 배정 대기
     Connected, // 상담 진행 중
     Ended,     // 종료됨
 }
 
+impl From<String> for RoomStatus {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "connected" => Self::Connected,
+            "ended" => Self::Ended,
+            _ => Self::Waiting,
+        }
+    }
+}
+
+impl fmt::Display for RoomStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoomStatus::Waiting => write!(f, "waiting"),
+            RoomStatus::Connected => write!(f, "connected"),
+            RoomStatus::Ended => write!(f, "ended"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ChatRooms {
-    rooms: Arc<RwLock<HashMap<ChatRoomId, ChatRoom>>>,
+    store: Arc<dyn RoomStore>,
+    repository: ChatRoomRepository,
 }
 
 impl ChatRooms {
-    pub fn new() -> Self {
-        Self {
-            rooms: Arc::new(RwLock::new(HashMap::new())),
+    pub fn new(store: Arc<dyn RoomStore>, repository: ChatRoomRepository) -> Self {
+        Self { store, repository }
+    }
+
+    // 서버 재시작 시 종료되지 않은 방을 DB에서 다시 불러온다
+    // (다른 인스턴스가 이미 관리 중인 방은 건드리지 않고, 새로 불러온 방만 돌려준다)
+    pub async fn restore(&self) -> MangJooResult<Vec<RestoredRoom>> {
+        let restored = self.repository.find_not_ended().await?;
+
+        self.insert_restored(restored, Utc::now()).await
+    }
+
+    // 다른 인스턴스가 이미 저장소에 올려둔 방은 저장소의 상태 그대로 돌려준다
+    pub async fn insert_restored(
+        &self,
+        rooms: Vec<ChatRoom>,
+        restored_at: DateTime<Utc>,
+    ) -> MangJooResult<Vec<RestoredRoom>> {
+        let mut restored = Vec::new();
+        for mut room in rooms {
+            room.mark_restored(restored_at);
+            if self.store.insert_if_absent(&room).await? {
+                restored.push(RestoredRoom {
+                    room,
+                    inserted: true,
+                });
+            } else if let Some(room) = self.store.get(&room.room_id).await? {
+                restored.push(RestoredRoom {
+                    room,
+                    inserted: false,
+                });
+            }
         }
+
+        Ok(restored)
     }
 
-    pub async fn create_room(&self, customer_id: i64) -> MangJooResult<String> {
+    pub async fn create_room(
+        &self,
+        customer: &UserSession,
+        new_room: NewRoom,
+        priority: i32,
+    ) -> MangJooResult<String> {
         let room_id = Uuid::new_v4().to_string();
         let now = Utc::now();
 
         let chat_room = ChatRoom {
             room_id: ChatRoomId(room_id.to_string()),
-            customer_id: customer_id.to_string(),
+            customer_id: customer.user_id,
+            customer_name: customer.name().to_string(),
+            customer_email: customer.email().to_string(),
+            topic: new_room.topic,
+            language: new_room.language,
+            queue: new_room.queue.unwrap_or_else(default_queue),
+            priority,
             agent_id: None,
             status: RoomStatus::Waiting,
             created_at: now,
             updated_at: now,
+            assigned_at: None,
+            first_assigned_at: None,
+            participants: HashMap::new(),
+            end: None,
         };
 
-        {
-            let mut rooms = self.rooms.write().await;
-            let chat_room_id = ChatRoomId(room_id.to_string());
-            rooms.insert(chat_room_id, chat_room);
-            println!("Rooms are {:?}", rooms);
-        }
+        self.repository.save(&chat_room).await?;
+        self.store.insert(&chat_room).await?;
 
         Ok(room_id.to_string())
     }
 
-    pub async fn is_available_room(&self, chat_room_id: &ChatRoomId, user_id: i64) -> bool {
-        let rooms = self.rooms.read().await;
-        let room = rooms.get(chat_room_id);
+    // 진행 중이 아닌 (종료된) 방은 DB에서 조회한다
+    pub async fn find_room(&self, chat_room_id: &ChatRoomId) -> MangJooResult<Option<ChatRoom>> {
+        if let Some(room) = self.store.get(chat_room_id).await? {
+            return Ok(Some(room));
+        }
+
+        self.repository.find_by_id(chat_room_id).await
+    }
+
+    // 진행 중인 방은 저장소의 최신 상태로 보여준다
+    pub async fn find_rooms_by_customer(&self, customer_id: i64) -> MangJooResult<Vec<ChatRoom>> {
+        let mut rooms = self.repository.find_by_customer(customer_id).await?;
+        for room in rooms.iter_mut() {
+            if room.status == RoomStatus::Ended {
+                continue;
+            }
+            if let Some(active) = self.store.get(&room.room_id).await? {
+                *room = active;
+            }
+        }
+
+        Ok(rooms)
+    }
+
+    // 고객 본인, 배정된 상담원, 또는 대기 중인 방을 직접 맡으려는 상담원만 입장 가능
+    pub async fn is_available_room(
+        &self,
+        chat_room_id: &ChatRoomId,
+        user_session: &UserSession,
+    ) -> bool {
+        let room = match self.store.get(chat_room_id).await {
+            Ok(room) => room,
+            Err(err) => {
+                tracing::error!("Can't load chat room {:?}", err);
+                None
+            }
+        };
         if let Some(room) = room {
-            room.customer_id == user_id.to_string()
+            // 팀장은 진행 중인 모든 방을 모니터링할 수 있다
+            if user_session.is_supervisor() {
+                room.status != RoomStatus::Ended
+            } else if user_session.is_agent() {
+                match room.agent_id {
+                    Some(agent_id) => agent_id == user_session.user_id,
+                    None => room.status == RoomStatus::Waiting,
+                }
+            } else {
+                room.customer_id == user_session.user_id
+            }
         } else {
             false
         }
     }
 
+    pub async fn find_active_rooms_by_agent(&self, agent_id: i64) -> HashSet<ChatRoomId> {
+        let rooms = self.store.list().await.unwrap_or_else(|err| {
+            tracing::error!("Can't load chat rooms {:?}", err);
+            Vec::new()
+        });
+        rooms
+            .into_iter()
+            .filter(|room| room.agent_id == Some(agent_id) && room.status == RoomStatus::Connected)
+            .map(|room| room.room_id)
+            .collect()
+    }
+
+    pub async fn find_active_rooms(&self) -> MangJooResult<Vec<ChatRoom>> {
+        let mut rooms: Vec<ChatRoom> = self
+            .store
+            .list()
+            .await?
+            .into_iter()
+            .filter(|room| room.status != RoomStatus::Ended)
+            .collect();
+        rooms.sort_by_key(|room| room.created_at);
+
+        Ok(rooms)
+    }
+
+    pub async fn is_assigned_to(&self, chat_room_id: &ChatRoomId, agent_id: i64) -> bool {
+        matches!(
+            self.store.get(chat_room_id).await,
+            Ok(Some(room)) if room.agent_id == Some(agent_id)
+        )
+    }
+
+    // 저장소에서 원자적으로 배정되므로 같은 방이 두 번 배정되지 않는다
     pub async fn enter_room(
         &self,
         role: UserRole,
         chat_room_id: ChatRoomId,
         user_id: i64,
     ) -> MangJooResult<()> {
-        if role.is_user() {
+        if !role.is_agent() {
             return Err(AppError::InvalidRequest("Can't enter on user".to_string()));
         }
 
-        let mut rooms = self.rooms.write().await;
-        let room = rooms
-            .get_mut(&chat_room_id)
+        let room = self
+            .store
+            .update(&chat_room_id, &|room: &mut ChatRoom| {
+                room.enter_agent(user_id)
+            })
+            .await?
             .ok_or_else(|| AppError::InvalidRequest("Not found chat room".to_string()))?;
-
-        let _ = room.enter_agent(user_id.to_string())?;
+        self.persist(&room).await?;
 
         Ok(())
     }
 
-    pub async fn remove_room(&self, chat_room_id: &ChatRoomId) -> MangJooResult<()> {
-        let mut rooms = self.rooms.write().await;
-        let _ = rooms.remove(chat_room_id);
+    pub async fn end_chat(
+        &self,
+        chat_room_id: &ChatRoomId,
+        end: ChatEnd,
+    ) -> MangJooResult<ChatRoom> {
+        let room = self
+            .store
+            .update(chat_room_id, &|room: &mut ChatRoom| {
+                room.end_chat(end.clone())
+            })
+            .await?
+            .ok_or_else(|| AppError::InvalidRequest("Not found chat room".to_string()))?;
+        self.persist(&room).await?;
+
+        Ok(room)
+    }
+
+    pub async fn transfer_to_agent(
+        &self,
+        chat_room_id: &ChatRoomId,
+        from_agent_id: i64,
+        to_agent_id: i64,
+    ) -> MangJooResult<ChatRoom> {
+        let room = self
+            .store
+            .update(chat_room_id, &|room: &mut ChatRoom| {
+                room.transfer_to_agent(from_agent_id, to_agent_id)
+            })
+            .await?
+            .ok_or_else(|| AppError::InvalidRequest("Not found chat room".to_string()))?;
+        self.persist(&room).await?;
+
+        Ok(room)
+    }
+
+    // 에스컬레이션된 방은 대기열 맨 앞으로 보낸다
+    pub async fn return_to_queue(
+        &self,
+        chat_room_id: &ChatRoomId,
+        from_agent_id: i64,
+        escalated: bool,
+    ) -> MangJooResult<ChatRoom> {
+        let room = self
+            .store
+            .update(chat_room_id, &|room: &mut ChatRoom| {
+                room.return_to_queue(from_agent_id)
+            })
+            .await?
+            .ok_or_else(|| AppError::InvalidRequest("Not found chat room".to_string()))?;
+        self.persist(&room).await?;
+
+        if escalated {
+            self.requeue(chat_room_id).await?;
+        } else {
+            self.enqueue(chat_room_id).await?;
+        }
+
+        Ok(room)
+    }
+
+    // 재접속인 경우 true
+    pub async fn connect(&self, chat_room_id: &ChatRoomId, user_id: i64) -> MangJooResult<bool> {
+        let reconnecting = self
+            .store
+            .get(chat_room_id)
+            .await?
+            .is_some_and(|room| room.is_reconnecting(user_id));
+
+        self.store
+            .update(chat_room_id, &|room: &mut ChatRoom| {
+                room.connect_participant(user_id)
+            })
+            .await?
+            .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", chat_room_id.0)))?;
+
+        Ok(reconnecting)
+    }
+
+    pub async fn disconnect(
+        &self,
+        chat_room_id: &ChatRoomId,
+        user_id: i64,
+    ) -> MangJooResult<Option<ChatRoom>> {
+        self.store
+            .update(chat_room_id, &|room: &mut ChatRoom| {
+                room.disconnect_participant(user_id);
+                Ok(())
+            })
+            .await
+    }
+
+    // 유예 시간 동안 아무도 다시 접속하지 않은 방만 종료한다
+    pub async fn end_if_abandoned(
+        &self,
+        chat_room_id: &ChatRoomId,
+        grace_period: chrono::Duration,
+    ) -> MangJooResult<bool> {
+        let abandoned = self
+            .store
+            .get(chat_room_id)
+            .await?
+            .is_some_and(|room| room.is_abandoned(grace_period));
+        if !abandoned {
+            return Ok(false);
+        }
+
+        // 확인 이후 재접속한 경우 갱신하지 않는다
+        let ended = self
+            .store
+            .update(chat_room_id, &|room: &mut ChatRoom| {
+                if !room.is_abandoned(grace_period) {
+                    return Err(AppError::InvalidRequest("Room is in use".to_string()));
+                }
+                room.end_chat(ChatEnd::timeout())
+            })
+            .await;
+
+        // DB 에는 방을 정리할 때(remove_room) 종료로 기록한다
+        match ended {
+            Ok(Some(_)) => Ok(true),
+            Ok(None) => Ok(false),
+            Err(AppError::InvalidRequest(_)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn remove_room(&self, chat_room_id: &ChatRoomId) -> MangJooResult<Option<ChatRoom>> {
+        self.dequeue(chat_room_id).await?;
+        let removed = self.store.remove(chat_room_id).await?;
+
+        // 진행 목록에서 내려가는 방은 DB에서도 종료 처리해 재시작 시 복구되지 않도록 한다
+        // 끝내 기록하지 못하면 재시작 때 복구된 뒤 다시 정리되므로 상담원 자리는 그대로 돌려준다
+        if let Some(room) = removed.as_ref() {
+            let mut ended = room.clone();
+            if ended.status != RoomStatus::Ended {
+                ended.end_chat(ChatEnd::timeout())?;
+            }
+            if let Err(err) = self.persist(&ended).await {
+                tracing::error!(
+                    "Chat room {} stays in progress in DB until restart {:?}",
+                    ended.room_id.0,
+                    err
+                );
+            }
+        }
+
+        Ok(removed)
+    }
+
+    // 방의 대기열에서 같은 우선순위의 맨 뒤에 넣는다
+    pub async fn enqueue(&self, chat_room_id: &ChatRoomId) -> MangJooResult<()> {
+        let room = self.find_waiting_room(chat_room_id).await?;
+        let score = queue_score(room.priority, Utc::now().timestamp_millis());
+        self.store.enqueue(&room.queue, chat_room_id, score).await
+    }
+
+    // 배정에 실패했거나 에스컬레이션된 방은 같은 우선순위의 맨 앞으로 되돌린다
+    pub async fn requeue(&self, chat_room_id: &ChatRoomId) -> MangJooResult<()> {
+        let room = self.find_waiting_room(chat_room_id).await?;
+        let score = queue_score(room.priority, 0);
+        self.store.enqueue(&room.queue, chat_room_id, score).await
+    }
 
+    pub async fn dequeue(&self, chat_room_id: &ChatRoomId) -> MangJooResult<()> {
+        if let Some(room) = self.store.get(chat_room_id).await? {
+            self.store.take_waiting(&room.queue, chat_room_id).await?;
+        }
         Ok(())
     }
+
+    async fn find_waiting_room(&self, chat_room_id: &ChatRoomId) -> MangJooResult<ChatRoom> {
+        self.store
+            .get(chat_room_id)
+            .await?
+            .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", chat_room_id.0)))
+    }
+
+    // 고객이 이전 상담에서 만난 상담원 (조회에 실패하면 없는 것으로 본다)
+    pub async fn find_previous_agent(&self, room: &ChatRoom) -> Option<i64> {
+        match self
+            .repository
+            .find_previous_agent(room.customer_id, &room.room_id)
+            .await
+        {
+            Ok(agent_id) => agent_id,
+            Err(err) => {
+                tracing::error!("Can't load previous agent {:?}", err);
+                None
+            }
+        }
+    }
+
+    // 대기열에서 특정 방을 꺼낸다 (다른 인스턴스가 먼저 꺼냈으면 false)
+    pub async fn take_waiting(
+        &self,
+        queue: &str,
+        chat_room_id: &ChatRoomId,
+    ) -> MangJooResult<bool> {
+        self.store.take_waiting(queue, chat_room_id).await
+    }
+
+    pub async fn waiting_rooms(&self, queue: &str) -> MangJooResult<Vec<ChatRoomId>> {
+        self.store.waiting_rooms(queue).await
+    }
+
+    // 대기열에서 최근 배정된 방들의 평균 대기 시간 (초)
+    pub async fn recent_average_wait_seconds(&self, queue: &str) -> MangJooResult<Option<f64>> {
+        self.repository
+            .average_wait_seconds(queue, RECENT_ASSIGNMENT_SAMPLE)
+            .await
+    }
+
+    // 진행 상태는 저장소가 기준이고, DB 는 기록과 재시작 복구용이다
+    // 일시적인 오류는 몇 번 다시 시도하고, 그래도 실패하면 호출한 쪽에 알린다
+    async fn persist(&self, room: &ChatRoom) -> MangJooResult<()> {
+        let mut attempt = 1;
+        loop {
+            match self.repository.update(room).await {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt >= PERSIST_ATTEMPTS => {
+                    tracing::error!("Can't persist chat room {:?}", err);
+                    return Err(err);
+                }
+                Err(err) => {
+                    tracing::warn!("Retry persisting chat room {:?}", err);
+                    tokio::time::sleep(PERSIST_RETRY_DELAY * attempt).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
 }
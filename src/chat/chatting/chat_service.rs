@@ -1,9 +1,756 @@
-use crate::config::MangJooResult;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
 
-use super::{chat_room::ChatRooms, ChatRoomId};
+use crate::{
+    chat::{
+        agent::agent::Agents,
+        customer::customer_repository::CustomerRepository,
+        offline::{offline_repository::OfflineMessageRepository, offline_service},
+        queue::{chat_queue::DEFAULT_QUEUE, queue_repository::ChatQueueRepository, queue_service},
+    },
+    config::{error::AppError, session::UserSession, MangJooResult},
+    user::user::UserRole,
+};
 
-pub async fn create_room(customer_id: i64, chat_rooms: &ChatRooms) -> MangJooResult<ChatRoomId> {
-    let create_room = chat_rooms.create_room(customer_id).await?;
+use super::{
+    attachment_storage::AttachmentStorage,
+    chat_attachment::Attachment,
+    chat_event::ChatEvent,
+    chat_message::ChatMessage,
+    chat_repository::{
+        AttachmentRepository, ChatMessageRepository, ChatSlaRepository, ChatSurveyRepository,
+        ChatTransferRepository,
+    },
+    chat_room::{ChatEnd, ChatRoom, ChatRooms, CreatedRoom, NewRoom, RoomStatus},
+    chat_sla::{RoomSla, SlaBreach, SlaMetric},
+    chat_survey::ChatSurvey,
+    chat_transfer::{ChatTransfer, TransferRequest, TransferTarget},
+    queue_status::QueueStatus,
+    ChatRoomId,
+};
 
-    Ok(ChatRoomId(create_room))
+const DEFAULT_MESSAGE_LIMIT: i64 = 50;
+const MAX_MESSAGE_LIMIT: i64 = 100;
+// 상담원 입장 시 함께 보내는 최근 메시지 수
+const CONTEXT_MESSAGE_LIMIT: i64 = 20;
+const MAX_SURVEY_COMMENT_LENGTH: usize = 1000;
+const MAX_TRANSFER_NOTE_LENGTH: usize = 1000;
+// 위반 목록을 기간 없이 조회하면 최근 하루
+const DEFAULT_BREACH_WINDOW_HOURS: i64 = 24;
+const DEFAULT_BREACH_LIMIT: i64 = 100;
+const MAX_BREACH_LIMIT: i64 = 500;
+const MAX_SKILL_LENGTH: usize = 50;
+
+// 진행 목록에서 방을 내리고 상담원을 다음 상담에 배정할 수 있게 한다
+// (상담원이 풀려났으면 그 상담원 id)
+pub async fn close_room(
+    room_id: &ChatRoomId,
+    chat_rooms: &ChatRooms,
+    agents: &Agents,
+) -> MangJooResult<Option<i64>> {
+    let Some(room) = chat_rooms.remove_room(room_id).await? else {
+        return Ok(None);
+    };
+    let Some(agent_id) = room.agent_id else {
+        return Ok(None);
+    };
+    agents.release_room(agent_id, room_id).await;
+
+    Ok(Some(agent_id))
+}
+
+// 고객 등급이 높을수록 같은 대기열에서 먼저 배정된다
+// 대기열이 영업 시간 밖이면 방 대신 오프라인 문의를 남긴다
+pub async fn create_room(
+    customer: &UserSession,
+    new_room: NewRoom,
+    chat_rooms: &ChatRooms,
+    customers: &CustomerRepository,
+    chat_queues: &ChatQueueRepository,
+    offline_messages: &OfflineMessageRepository,
+) -> MangJooResult<CreatedRoom> {
+    let queue = queue_service::find_queue(
+        new_room.queue.as_deref().unwrap_or(DEFAULT_QUEUE),
+        chat_queues,
+    )
+    .await?;
+    let topic = normalize_skill("topic", new_room.topic)?;
+    let language = normalize_skill("language", new_room.language)?;
+
+    let availability = queue_service::find_availability(&queue, chat_queues).await?;
+    if !availability.open {
+        let offline_message = offline_service::leave_message(
+            customer.user_id,
+            &queue.name,
+            topic.as_deref(),
+            language.as_deref(),
+            new_room.message.as_deref(),
+            offline_messages,
+        )
+        .await?;
+
+        return Ok(CreatedRoom::Offline {
+            offline_message,
+            next_open_at: availability.next_open_at,
+        });
+    }
+
+    let new_room = NewRoom {
+        topic,
+        language,
+        queue: Some(queue.name),
+        message: None,
+    };
+    let priority = customers.find_tier(customer.user_id).await?.priority();
+    let create_room = chat_rooms.create_room(customer, new_room, priority).await?;
+
+    Ok(CreatedRoom::Online(ChatRoomId(create_room)))
+}
+
+// 주제와 언어는 상담원 스킬 이름과 비교하므로 소문자로 맞춘다 (빈 값은 지정하지 않은 것)
+pub fn normalize_skill(field: &str, value: Option<String>) -> MangJooResult<Option<String>> {
+    let Some(value) = value.map(|value| value.trim().to_lowercase()) else {
+        return Ok(None);
+    };
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if value.chars().count() > MAX_SKILL_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "{} must be at most {} characters",
+            field, MAX_SKILL_LENGTH
+        )));
+    }
+
+    Ok(Some(value))
+}
+
+pub async fn assign_agent(
+    room_id: &ChatRoomId,
+    agent_id: i64,
+    chat_rooms: &ChatRooms,
+    agents: &Agents,
+) -> MangJooResult<()> {
+    chat_rooms
+        .enter_room(UserRole::Agent, room_id.clone(), agent_id)
+        .await?;
+
+    // 상담원 목록에 없는 상담원이 직접 입장한 경우에도 방 배정은 유지한다
+    if let Err(err) = agents.assign_room(agent_id, room_id.clone()).await {
+        tracing::warn!("Agent {} is not registered {:?}", agent_id, err);
+    }
+
+    Ok(())
+}
+
+pub async fn find_room_context(
+    room_id: &ChatRoomId,
+    chat_rooms: &ChatRooms,
+    chat_messages: &ChatMessageRepository,
+) -> MangJooResult<ChatEvent> {
+    let room = chat_rooms
+        .find_room(room_id)
+        .await?
+        .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", room_id.0)))?;
+
+    let mut recent_messages = chat_messages
+        .find_by_room(room_id, None, CONTEXT_MESSAGE_LIMIT)
+        .await?;
+    recent_messages.reverse();
+
+    Ok(ChatEvent::context(&room, recent_messages))
+}
+
+pub async fn find_messages(
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    before: Option<i64>,
+    limit: Option<i64>,
+    chat_rooms: &ChatRooms,
+    chat_messages: &ChatMessageRepository,
+) -> MangJooResult<MessagePage> {
+    let room = chat_rooms
+        .find_room(room_id)
+        .await?
+        .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", room_id.0)))?;
+
+    // 고객은 본인 방만, 상담원과 팀장은 이전 상담 기록까지 조회 가능
+    if !user_session.is_agent()
+        && !user_session.is_supervisor()
+        && room.customer_id != user_session.user_id
+    {
+        return Err(AppError::Unauthorized(
+            "Not a member of this chat room".to_string(),
+        ));
+    }
+
+    let limit = limit
+        .unwrap_or(DEFAULT_MESSAGE_LIMIT)
+        .clamp(1, MAX_MESSAGE_LIMIT);
+
+    // 다음 페이지 존재 여부 확인을 위해 하나 더 조회
+    let mut messages = chat_messages
+        .find_by_room(room_id, before, limit + 1)
+        .await?;
+
+    let next_cursor = if messages.len() as i64 > limit {
+        messages.truncate(limit as usize);
+        messages.last().map(|message| message.message_id)
+    } else {
+        None
+    };
+    messages.reverse();
+
+    let message_ids: Vec<i64> = messages.iter().map(|message| message.message_id).collect();
+    for (message_id, receipt) in chat_messages.find_receipts(&message_ids).await? {
+        if let Some(message) = messages
+            .iter_mut()
+            .find(|message| message.message_id == message_id)
+        {
+            message.receipts.push(receipt);
+        }
+    }
+
+    Ok(MessagePage {
+        messages,
+        next_cursor,
+    })
+}
+
+// 방의 고객 또는 배정된 상담원만 파일을 올릴 수 있다
+pub async fn upload_attachment(
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    file_name: &str,
+    bytes: Bytes,
+    chat_rooms: &ChatRooms,
+    attachments: &AttachmentRepository,
+    storage: &dyn AttachmentStorage,
+) -> MangJooResult<Attachment> {
+    let room = find_member_room(room_id, user_session, chat_rooms).await?;
+    if room.status == RoomStatus::Ended {
+        return Err(AppError::InvalidRequest(
+            "This chat room is ended".to_string(),
+        ));
+    }
+
+    let attachment = Attachment::new(room_id.clone(), user_session.user_id, file_name, &bytes)?;
+    storage.put(&attachment.storage_key, bytes).await?;
+    attachments.save(&attachment).await?;
+
+    Ok(attachment)
+}
+
+// 방의 고객과 배정된 상담원, 팀장, 관리자가 파일을 받을 수 있다
+pub async fn download_attachment(
+    attachment_id: &str,
+    user_session: &UserSession,
+    chat_rooms: &ChatRooms,
+    attachments: &AttachmentRepository,
+    storage: &dyn AttachmentStorage,
+) -> MangJooResult<(Attachment, Bytes)> {
+    let attachment = attachments
+        .find_by_id(attachment_id)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest("Attachment not found".to_string()))?;
+    // 팀장과 관리자는 모니터링하는 모든 방의 파일을 받을 수 있다
+    if !user_session.is_supervisor() && !user_session.is_admin() {
+        find_member_room(&attachment.room_id, user_session, chat_rooms).await?;
+    }
+
+    let bytes = storage.get(&attachment.storage_key).await?;
+    Ok((attachment, bytes))
+}
+
+// 같은 방에 본인이 올린 파일만 메시지로 보낼 수 있다
+pub async fn check_sendable_attachment(
+    room_id: &ChatRoomId,
+    attachment_id: &str,
+    user_session: &UserSession,
+    attachments: &AttachmentRepository,
+) -> MangJooResult<()> {
+    let attachment = attachments
+        .find_by_id(attachment_id)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest("Attachment not found".to_string()))?;
+    if &attachment.room_id != room_id || attachment.uploader_id != user_session.user_id {
+        return Err(AppError::InvalidRequest(
+            "Attachment does not belong to this chat room".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn find_member_room(
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    chat_rooms: &ChatRooms,
+) -> MangJooResult<ChatRoom> {
+    let room = chat_rooms
+        .find_room(room_id)
+        .await?
+        .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", room_id.0)))?;
+
+    let is_member =
+        room.customer_id == user_session.user_id || room.agent_id == Some(user_session.user_id);
+    if !is_member {
+        return Err(AppError::Unauthorized(
+            "Not a member of this chat room".to_string(),
+        ));
+    }
+
+    Ok(room)
+}
+
+// 대기열 안의 모든 방의 순번과 예상 대기 시간
+pub async fn find_queue_statuses(
+    queue: &str,
+    chat_rooms: &ChatRooms,
+    agents: &Agents,
+) -> MangJooResult<Vec<QueueStatus>> {
+    let waiting_rooms = chat_rooms.waiting_rooms(queue).await?;
+    if waiting_rooms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let queue_length = waiting_rooms.len();
+    let available_agents = agents.count_available_agents(queue).await?;
+    let average_wait_seconds = chat_rooms.recent_average_wait_seconds(queue).await?;
+
+    Ok(waiting_rooms
+        .into_iter()
+        .enumerate()
+        .map(|(index, room_id)| {
+            QueueStatus::estimate(
+                room_id,
+                queue,
+                index + 1,
+                queue_length,
+                available_agents,
+                average_wait_seconds,
+            )
+        })
+        .collect())
+}
+
+// 고객은 본인 방만, 상담원과 팀장, 관리자는 모든 방의 대기 상태를 조회할 수 있다
+pub async fn find_queue_status(
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    chat_rooms: &ChatRooms,
+    agents: &Agents,
+) -> MangJooResult<QueueStatus> {
+    let room = chat_rooms
+        .find_room(room_id)
+        .await?
+        .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", room_id.0)))?;
+    if user_session.is_user() && room.customer_id != user_session.user_id {
+        return Err(AppError::Unauthorized(
+            "Not a member of this chat room".to_string(),
+        ));
+    }
+    if room.status != RoomStatus::Waiting {
+        return Err(AppError::InvalidRequest(
+            "This chat room is not waiting".to_string(),
+        ));
+    }
+
+    find_queue_statuses(&room.queue, chat_rooms, agents)
+        .await?
+        .into_iter()
+        .find(|queue_status| &queue_status.room_id == room_id)
+        .ok_or_else(|| AppError::InvalidRequest("This chat room is not in the queue".to_string()))
+}
+
+pub async fn find_room_sla(
+    room_id: &ChatRoomId,
+    chat_sla: &ChatSlaRepository,
+) -> MangJooResult<RoomSla> {
+    let timeline = chat_sla
+        .find_timeline(room_id)
+        .await?
+        .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", room_id.0)))?;
+    let breaches = chat_sla.find_breaches_by_room(room_id).await?;
+
+    Ok(RoomSla::new(timeline, breaches))
+}
+
+pub async fn find_sla_breaches(
+    queue: Option<&str>,
+    metric: Option<SlaMetric>,
+    since: Option<DateTime<Utc>>,
+    limit: Option<i64>,
+    chat_sla: &ChatSlaRepository,
+) -> MangJooResult<Vec<SlaBreach>> {
+    let since =
+        since.unwrap_or_else(|| Utc::now() - chrono::Duration::hours(DEFAULT_BREACH_WINDOW_HOURS));
+    let limit = limit
+        .unwrap_or(DEFAULT_BREACH_LIMIT)
+        .clamp(1, MAX_BREACH_LIMIT);
+
+    chat_sla.find_breaches(queue, metric, since, limit).await
+}
+
+// 팀장은 배정된 상담원에게, 배정된 상담원은 팀장에게 귓속말을 보낸다
+pub async fn whisper(
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    body: String,
+    chat_rooms: &ChatRooms,
+) -> MangJooResult<ChatEvent> {
+    let room = chat_rooms
+        .find_room(room_id)
+        .await?
+        .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", room_id.0)))?;
+    let agent_id = room.agent_id.ok_or_else(|| {
+        AppError::InvalidRequest("The chat room has no assigned agent".to_string())
+    })?;
+    if !user_session.is_supervisor() && user_session.user_id != agent_id {
+        return Err(AppError::Unauthorized(
+            "Only supervisors and the assigned agent can whisper".to_string(),
+        ));
+    }
+
+    Ok(ChatEvent::whisper(user_session, agent_id, body))
+}
+
+// 상담원 또는 고객이 상담을 종료한다 (상담원 해제는 방을 정리할 때 함께 한다)
+pub async fn end_chat(
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    reason: Option<String>,
+    summary: Option<String>,
+    chat_rooms: &ChatRooms,
+) -> MangJooResult<ChatRoom> {
+    find_member_room(room_id, user_session, chat_rooms).await?;
+    if summary.is_some() && !user_session.is_agent() {
+        return Err(AppError::InvalidRequest(
+            "Only agents can write a summary".to_string(),
+        ));
+    }
+
+    let end = ChatEnd::by_user(user_session, reason, summary);
+    chat_rooms.end_chat(room_id, end).await
+}
+
+// 종료된 본인 상담에 한 번만 응답할 수 있다
+pub async fn submit_survey(
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    rating: i16,
+    comment: Option<String>,
+    chat_rooms: &ChatRooms,
+    chat_surveys: &ChatSurveyRepository,
+) -> MangJooResult<ChatSurvey> {
+    check_survey(rating, comment.as_deref())?;
+
+    let room = chat_rooms
+        .find_room(room_id)
+        .await?
+        .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", room_id.0)))?;
+    if room.customer_id != user_session.user_id {
+        return Err(AppError::Unauthorized(
+            "Not a member of this chat room".to_string(),
+        ));
+    }
+    if room.status != RoomStatus::Ended {
+        return Err(AppError::InvalidRequest(
+            "This chat room is not ended".to_string(),
+        ));
+    }
+
+    chat_surveys
+        .save(room_id, user_session.user_id, rating, comment.as_deref())
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest("Survey already submitted".to_string()))
+}
+
+// 배정된 상담원(또는 관리자, 팀장)이 방을 다른 상담원이나 대기열로 넘긴다
+pub async fn transfer_room(
+    room_id: &ChatRoomId,
+    user_session: &UserSession,
+    request: TransferRequest,
+    chat_rooms: &ChatRooms,
+    agents: &Agents,
+    chat_transfers: &ChatTransferRepository,
+) -> MangJooResult<ChatTransfer> {
+    let TransferRequest {
+        target,
+        note,
+        escalated,
+    } = request;
+    if note
+        .as_ref()
+        .is_some_and(|note| note.chars().count() > MAX_TRANSFER_NOTE_LENGTH)
+    {
+        return Err(AppError::InvalidRequest(format!(
+            "Note must be at most {} characters",
+            MAX_TRANSFER_NOTE_LENGTH
+        )));
+    }
+
+    let room = chat_rooms
+        .find_room(room_id)
+        .await?
+        .ok_or_else(|| AppError::RoomNotFound(format!("Room Id = {}", room_id.0)))?;
+    let from_agent_id = room.agent_id.ok_or_else(|| {
+        AppError::InvalidRequest("The chat room has no assigned agent".to_string())
+    })?;
+    if from_agent_id != user_session.user_id
+        && !user_session.is_admin()
+        && !user_session.is_supervisor()
+    {
+        return Err(AppError::Unauthorized(
+            "Only the assigned agent can transfer this chat room".to_string(),
+        ));
+    }
+
+    let to_agent_id = match target {
+        TransferTarget::Agent { agent_id } => {
+            // 받는 상담원의 자리를 먼저 확보하고, 방 이관에 실패하면 되돌린다
+            agents.try_assign_room(agent_id, room_id.clone()).await?;
+            if let Err(err) = chat_rooms
+                .transfer_to_agent(room_id, from_agent_id, agent_id)
+                .await
+            {
+                // DB 기록만 실패한 경우에는 이미 넘어간 방이므로 자리를 유지한다
+                if !chat_rooms.is_assigned_to(room_id, agent_id).await {
+                    agents.release_room(agent_id, room_id).await;
+                }
+                return Err(err);
+            }
+            Some(agent_id)
+        }
+        TransferTarget::Queue => {
+            chat_rooms
+                .return_to_queue(room_id, from_agent_id, escalated)
+                .await?;
+            None
+        }
+    };
+    agents.release_room(from_agent_id, room_id).await;
+
+    chat_transfers
+        .save(
+            room_id,
+            from_agent_id,
+            to_agent_id,
+            note.as_deref(),
+            escalated,
+            user_session.user_id,
+        )
+        .await
+}
+
+// 팀장이 이관 건을 맡는다 (이미 맡은 팀장이 있으면 실패)
+pub async fn claim_escalation(
+    transfer_id: i64,
+    supervisor_id: i64,
+    chat_transfers: &ChatTransferRepository,
+) -> MangJooResult<ChatTransfer> {
+    chat_transfers
+        .claim(transfer_id, supervisor_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::InvalidRequest(format!(
+                "Transfer {} is already claimed or not escalated",
+                transfer_id
+            ))
+        })
+}
+
+fn check_survey(rating: i16, comment: Option<&str>) -> MangJooResult<()> {
+    if !(1..=5).contains(&rating) {
+        return Err(AppError::InvalidRequest(
+            "Rating must be between 1 and 5".to_string(),
+        ));
+    }
+    if comment.is_some_and(|comment| comment.chars().count() > MAX_SURVEY_COMMENT_LENGTH) {
+        return Err(AppError::InvalidRequest(format!(
+            "Comment must be at most {} characters",
+            MAX_SURVEY_COMMENT_LENGTH
+        )));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct MessagePage {
+    pub messages: Vec<ChatMessage>,
+    pub next_cursor: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    };
+
+    use crate::{
+        chat::agent::{
+            agent::{Agent, AgentStatus},
+            agent_repository::AgentRepository,
+            agent_store::{AgentStore, InMemoryAgentStore},
+        },
+        config::test_support::unreachable_pool,
+    };
+
+    use super::{
+        super::{
+            chat_repository::ChatRoomRepository, chat_room::RECONNECT_GRACE_SECS,
+            room_store::InMemoryRoomStore,
+        },
+        *,
+    };
+
+    const AGENT_ID: i64 = 7;
+
+    fn connected_room(room_id: &str) -> ChatRoom {
+        let now = Utc::now();
+        ChatRoom {
+            room_id: ChatRoomId(room_id.to_string()),
+            customer_id: 1,
+            customer_name: "customer".to_string(),
+            customer_email: "customer@example.com".to_string(),
+            topic: None,
+            language: None,
+            queue: DEFAULT_QUEUE.to_string(),
+            priority: 0,
+            agent_id: Some(AGENT_ID),
+            status: RoomStatus::Connected,
+            created_at: now,
+            updated_at: now,
+            assigned_at: Some(now),
+            first_assigned_at: Some(now),
+            participants: HashMap::new(),
+            end: None,
+        }
+    }
+
+    async fn setup(room: &ChatRoom) -> (ChatRooms, Agents) {
+        let chat_rooms = ChatRooms::new(
+            Arc::new(InMemoryRoomStore::new()),
+            ChatRoomRepository::new(unreachable_pool()),
+        );
+
+        let agent_store = Arc::new(InMemoryAgentStore::new());
+        let agent = Agent::new(
+            AGENT_ID.to_string(),
+            "agent".to_string(),
+            AgentStatus::Available,
+            HashSet::from([room.room_id.clone()]),
+            1,
+            Utc::now(),
+        );
+        agent_store.insert(AGENT_ID, &agent).await.unwrap();
+        let agents = Agents::new(agent_store, AgentRepository::new(unreachable_pool()), 1);
+
+        (chat_rooms, agents)
+    }
+
+    #[tokio::test]
+    async fn restored_room_nobody_rejoins_is_closed_and_releases_agent() {
+        let room = connected_room("restored");
+        let (chat_rooms, agents) = setup(&room).await;
+        let grace_period = chrono::Duration::seconds(RECONNECT_GRACE_SECS);
+        let restored_at = Utc::now() - grace_period - chrono::Duration::seconds(1);
+
+        let restored = chat_rooms
+            .insert_restored(vec![room.clone()], restored_at)
+            .await
+            .unwrap();
+        assert_eq!(restored.len(), 1);
+        assert!(restored[0].inserted);
+
+        assert!(chat_rooms
+            .end_if_abandoned(&room.room_id, grace_period)
+            .await
+            .unwrap());
+        let released = close_room(&room.room_id, &chat_rooms, &agents)
+            .await
+            .unwrap();
+
+        assert_eq!(released, Some(AGENT_ID));
+        assert!(chat_rooms
+            .find_active_rooms_by_agent(AGENT_ID)
+            .await
+            .is_empty());
+        let agent = agents.find_agent(AGENT_ID).await.unwrap().unwrap();
+        assert!(agent.active_room_ids.is_empty());
+        assert!(agent.has_capacity());
+    }
+
+    #[tokio::test]
+    async fn restored_room_is_kept_during_grace_period() {
+        let room = connected_room("rejoining");
+        let (chat_rooms, _agents) = setup(&room).await;
+        let grace_period = chrono::Duration::seconds(RECONNECT_GRACE_SECS);
+
+        chat_rooms
+            .insert_restored(vec![room.clone()], Utc::now())
+            .await
+            .unwrap();
+
+        assert!(!chat_rooms
+            .end_if_abandoned(&room.room_id, grace_period)
+            .await
+            .unwrap());
+        assert_eq!(
+            chat_rooms.find_active_rooms_by_agent(AGENT_ID).await,
+            HashSet::from([room.room_id.clone()])
+        );
+    }
+
+    #[tokio::test]
+    async fn room_already_in_store_is_restored_for_abandon_check() {
+        let room = connected_room("shared");
+        let (chat_rooms, _agents) = setup(&room).await;
+        let grace_period = chrono::Duration::seconds(RECONNECT_GRACE_SECS);
+        let restored_at = Utc::now() - grace_period - chrono::Duration::seconds(1);
+
+        // 다른 인스턴스가 먼저 복구한 방
+        chat_rooms
+            .insert_restored(vec![room.clone()], restored_at)
+            .await
+            .unwrap();
+        let restored = chat_rooms
+            .insert_restored(vec![room.clone()], Utc::now())
+            .await
+            .unwrap();
+
+        assert_eq!(restored.len(), 1);
+        assert!(!restored[0].inserted);
+        assert!(chat_rooms
+            .end_if_abandoned(&room.room_id, grace_period)
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    fn survey_rating_must_be_between_one_and_five() {
+        for rating in 1..=5 {
+            assert!(check_survey(rating, None).is_ok());
+        }
+        for rating in [0, 6, -1] {
+            assert!(matches!(
+                check_survey(rating, None),
+                Err(AppError::InvalidRequest(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn survey_comment_is_limited() {
+        let longest = "a".repeat(MAX_SURVEY_COMMENT_LENGTH);
+        let too_long = "a".repeat(MAX_SURVEY_COMMENT_LENGTH + 1);
+
+        assert!(check_survey(5, Some(&longest)).is_ok());
+        assert!(matches!(
+            check_survey(5, Some(&too_long)),
+            Err(AppError::InvalidRequest(_))
+        ));
+    }
 }
@@ -0,0 +1,178 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ChatRoomId;
+
+// SLA 로 재는 지표
+// 배정 대기 = 방 생성 ~ 처음 배정, 첫 응답 = 처음 배정 ~ 상담원 첫 메시지, 처리 = 처음 배정 ~ 종료
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaMetric {
+    TimeToAssign,
+    FirstResponse,
+    HandleTime,
+}
+
+impl From<String> for SlaMetric {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "first_response" => Self::FirstResponse,
+            "handle_time" => Self::HandleTime,
+            _ => Self::TimeToAssign,
+        }
+    }
+}
+
+impl fmt::Display for SlaMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlaMetric::TimeToAssign => write!(f, "time_to_assign"),
+            SlaMetric::FirstResponse => write!(f, "first_response"),
+            SlaMetric::HandleTime => write!(f, "handle_time"),
+        }
+    }
+}
+
+// 목표를 넘긴 지표 (agent_id 가 없으면 감지 당시 대기 중이던 방)
+#[derive(Debug, Clone, Serialize)]
+pub struct SlaBreach {
+    pub room_id: ChatRoomId,
+    pub metric: SlaMetric,
+    pub queue: String,
+    pub agent_id: Option<i64>,
+    pub target_secs: i32,
+    pub breached_at: DateTime<Utc>,
+    pub detected_at: DateTime<Utc>,
+}
+
+// 방의 SLA 측정값 (아직 일어나지 않은 구간은 None)
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomSla {
+    pub room_id: ChatRoomId,
+    pub queue: String,
+    pub agent_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub first_assigned_at: Option<DateTime<Utc>>,
+    pub first_response_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub time_to_assign_secs: Option<i64>,
+    pub first_response_secs: Option<i64>,
+    pub handle_time_secs: Option<i64>,
+    pub breaches: Vec<SlaBreach>,
+}
+
+impl RoomSla {
+    pub fn new(timeline: RoomTimeline, breaches: Vec<SlaBreach>) -> Self {
+        Self {
+            time_to_assign_secs: elapsed_secs(
+                Some(timeline.created_at),
+                timeline.first_assigned_at,
+            ),
+            first_response_secs: elapsed_secs(
+                timeline.first_assigned_at,
+                timeline.first_response_at,
+            ),
+            handle_time_secs: elapsed_secs(timeline.first_assigned_at, timeline.ended_at),
+            room_id: timeline.room_id,
+            queue: timeline.queue,
+            agent_id: timeline.agent_id,
+            created_at: timeline.created_at,
+            first_assigned_at: timeline.first_assigned_at,
+            first_response_at: timeline.first_response_at,
+            ended_at: timeline.ended_at,
+            breaches,
+        }
+    }
+}
+
+// SLA 계산에 쓰는 방의 시각들
+#[derive(Debug, Clone)]
+pub struct RoomTimeline {
+    pub room_id: ChatRoomId,
+    pub queue: String,
+    pub agent_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub first_assigned_at: Option<DateTime<Utc>>,
+    pub first_response_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+fn elapsed_secs(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Option<i64> {
+    Some((to? - from?).num_seconds().max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn timeline(created_at: DateTime<Utc>) -> RoomTimeline {
+        RoomTimeline {
+            room_id: ChatRoomId("room".to_string()),
+            queue: "general".to_string(),
+            agent_id: None,
+            created_at,
+            first_assigned_at: None,
+            first_response_at: None,
+            ended_at: None,
+        }
+    }
+
+    #[test]
+    fn waiting_room_has_no_measurements() {
+        let sla = RoomSla::new(timeline(Utc::now()), Vec::new());
+
+        assert_eq!(sla.time_to_assign_secs, None);
+        assert_eq!(sla.first_response_secs, None);
+        assert_eq!(sla.handle_time_secs, None);
+    }
+
+    #[test]
+    fn measurements_start_from_first_assignment() {
+        let created_at = Utc::now() - Duration::minutes(30);
+        let first_assigned_at = created_at + Duration::seconds(90);
+        let sla = RoomSla::new(
+            RoomTimeline {
+                agent_id: Some(7),
+                first_assigned_at: Some(first_assigned_at),
+                first_response_at: Some(first_assigned_at + Duration::seconds(20)),
+                ended_at: Some(first_assigned_at + Duration::seconds(600)),
+                ..timeline(created_at)
+            },
+            Vec::new(),
+        );
+
+        assert_eq!(sla.time_to_assign_secs, Some(90));
+        assert_eq!(sla.first_response_secs, Some(20));
+        assert_eq!(sla.handle_time_secs, Some(600));
+    }
+
+    #[test]
+    fn ended_without_response_has_no_first_response() {
+        let created_at = Utc::now() - Duration::minutes(5);
+        let sla = RoomSla::new(
+            RoomTimeline {
+                first_assigned_at: Some(created_at + Duration::seconds(10)),
+                ended_at: Some(created_at + Duration::seconds(70)),
+                ..timeline(created_at)
+            },
+            Vec::new(),
+        );
+
+        assert_eq!(sla.first_response_secs, None);
+        assert_eq!(sla.handle_time_secs, Some(60));
+    }
+
+    #[test]
+    fn clock_skew_never_gives_negative_time() {
+        let created_at = Utc::now();
+
+        assert_eq!(
+            elapsed_secs(Some(created_at), Some(created_at - Duration::seconds(3))),
+            Some(0)
+        );
+    }
+}
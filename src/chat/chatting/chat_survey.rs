@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::ChatRoomId;
+
+// 상담 종료 후 고객 만족도 응답 (1~5점)
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatSurvey {
+    pub room_id: ChatRoomId,
+    pub customer_id: i64,
+    pub rating: i16,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ChatRoomId;
+
+// 방을 넘겨받을 대상
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransferTarget {
+    Agent { agent_id: i64 },
+    // 대기열로 되돌려 다른 상담원에게 배정한다
+    Queue,
+}
+
+// 이관 요청 (escalated 이면 대기열 맨 앞으로 보내고 팀장들에게 알린다)
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferRequest {
+    pub target: TransferTarget,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub escalated: bool,
+}
+
+// 방 이관 기록 (to_agent_id 가 없으면 대기열로 이관, claimed_by 는 escalated 건을 맡은 팀장)
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatTransfer {
+    pub transfer_id: i64,
+    pub room_id: ChatRoomId,
+    pub from_agent_id: i64,
+    pub to_agent_id: Option<i64>,
+    pub note: Option<String>,
+    pub escalated: bool,
+    pub transferred_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub claimed_by: Option<i64>,
+    pub claimed_at: Option<DateTime<Utc>>,
+}
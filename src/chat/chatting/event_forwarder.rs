@@ -0,0 +1,200 @@
+use tokio::sync::{broadcast, mpsc};
+
+use crate::config::session::UserSession;
+
+use super::chat_event::ChatEvent;
+
+// 연결 하나로 보낼 이벤트를 고른다 (방 채널과 이 연결에만 보내는 채널)
+// 이 연결의 채널이 닫히거나 방이 다른 상담원에게 넘어가면 남은 이벤트를 보낸 뒤 끝난다
+#[derive(Debug)]
+pub struct EventForwarder {
+    rx: broadcast::Receiver<ChatEvent>,
+    direct_rx: mpsc::UnboundedReceiver<ChatEvent>,
+    user_session: UserSession,
+    // 상담 종료와 만족도 요청은 연결마다 한 번만 보낸다
+    end_sent: bool,
+    survey_request_sent: bool,
+    // 방을 넘긴 상담원의 연결은 이관 이벤트까지만 보낸다
+    transferred_away: bool,
+}
+
+impl EventForwarder {
+    pub fn new(
+        rx: broadcast::Receiver<ChatEvent>,
+        direct_rx: mpsc::UnboundedReceiver<ChatEvent>,
+        user_session: UserSession,
+    ) -> Self {
+        Self {
+            rx,
+            direct_rx,
+            user_session,
+            end_sent: false,
+            survey_request_sent: false,
+            transferred_away: false,
+        }
+    }
+
+    // 다음에 보낼 이벤트 (None 이면 소켓을 닫는다)
+    pub async fn next(&mut self) -> Option<ChatEvent> {
+        if self.transferred_away {
+            return None;
+        }
+        loop {
+            let event = tokio::select! {
+                biased;
+                direct = self.direct_rx.recv() => direct?,
+                received = self.rx.recv() => match received {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Send task lagged, skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+            };
+
+            if self.should_send(&event) {
+                return Some(event);
+            }
+        }
+    }
+
+    fn should_send(&mut self, event: &ChatEvent) -> bool {
+        if !event.is_visible_to(&self.user_session) {
+            return false;
+        }
+
+        match event {
+            ChatEvent::End { .. } => !std::mem::replace(&mut self.end_sent, true),
+            ChatEvent::SurveyRequest { .. } => {
+                !std::mem::replace(&mut self.survey_request_sent, true)
+            }
+            ChatEvent::Transferred { from_agent_id, .. } => {
+                self.transferred_away = *from_agent_id == self.user_session.user_id;
+                true
+            }
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{chat::chatting::ChatRoomId, config::test_support::session, user::user::UserRole};
+
+    use super::*;
+
+    const CUSTOMER_ID: i64 = 1;
+    const AGENT_ID: i64 = 7;
+
+    fn forwarder(
+        user_session: UserSession,
+    ) -> (
+        broadcast::Sender<ChatEvent>,
+        mpsc::UnboundedSender<ChatEvent>,
+        EventForwarder,
+    ) {
+        let (tx, rx) = broadcast::channel(16);
+        let (direct_tx, direct_rx) = mpsc::unbounded_channel();
+        (
+            tx,
+            direct_tx,
+            EventForwarder::new(rx, direct_rx, user_session),
+        )
+    }
+
+    fn room_id() -> ChatRoomId {
+        serde_json::from_value("room".into()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn ending_customer_receives_survey_request_before_close() {
+        let customer = session(CUSTOMER_ID, UserRole::User);
+        let (tx, direct_tx, mut forwarder) = forwarder(customer.clone());
+
+        // 종료한 연결에는 직접 보내고, 방 채널로도 같은 이벤트가 돌아온다
+        let end = ChatEvent::end(&customer, None);
+        let survey_request = ChatEvent::survey_request(&room_id());
+        direct_tx.send(end.clone()).unwrap();
+        direct_tx.send(survey_request.clone()).unwrap();
+        tx.send(end).unwrap();
+        tx.send(survey_request).unwrap();
+        drop(direct_tx);
+
+        assert!(matches!(
+            forwarder.next().await,
+            Some(ChatEvent::End { .. })
+        ));
+        assert!(matches!(
+            forwarder.next().await,
+            Some(ChatEvent::SurveyRequest { .. })
+        ));
+        assert!(forwarder.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn end_and_survey_request_are_sent_once() {
+        let agent = session(AGENT_ID, UserRole::Agent);
+        let (tx, direct_tx, mut forwarder) = forwarder(agent.clone());
+
+        tx.send(ChatEvent::end(&session(CUSTOMER_ID, UserRole::User), None))
+            .unwrap();
+        tx.send(ChatEvent::survey_request(&room_id())).unwrap();
+        tx.send(ChatEvent::end(&agent, None)).unwrap();
+        tx.send(ChatEvent::system("closed")).unwrap();
+
+        assert!(matches!(
+            forwarder.next().await,
+            Some(ChatEvent::End { .. })
+        ));
+        assert!(matches!(
+            forwarder.next().await,
+            Some(ChatEvent::SurveyRequest { .. })
+        ));
+        assert!(matches!(
+            forwarder.next().await,
+            Some(ChatEvent::System { .. })
+        ));
+        drop(direct_tx);
+        assert!(forwarder.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn previous_agent_stops_receiving_after_transfer() {
+        let (tx, _direct_tx, mut forwarder) = forwarder(session(AGENT_ID, UserRole::Agent));
+
+        tx.send(ChatEvent::transferred(AGENT_ID, Some(8))).unwrap();
+        tx.send(ChatEvent::typing(
+            &session(CUSTOMER_ID, UserRole::User),
+            true,
+        ))
+        .unwrap();
+
+        assert!(matches!(
+            forwarder.next().await,
+            Some(ChatEvent::Transferred { .. })
+        ));
+        assert!(forwarder.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn new_agent_keeps_receiving_after_transfer() {
+        let (tx, _direct_tx, mut forwarder) = forwarder(session(8, UserRole::Agent));
+
+        tx.send(ChatEvent::transferred(AGENT_ID, Some(8))).unwrap();
+        tx.send(ChatEvent::typing(
+            &session(CUSTOMER_ID, UserRole::User),
+            true,
+        ))
+        .unwrap();
+
+        assert!(matches!(
+            forwarder.next().await,
+            Some(ChatEvent::Transferred { .. })
+        ));
+        assert!(matches!(
+            forwarder.next().await,
+            Some(ChatEvent::Typing { .. })
+        ));
+    }
+}
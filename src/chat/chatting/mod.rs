@@ -1,8 +1,22 @@
 use serde::{Deserialize, Serialize};
 
+pub mod attachment_storage;
+pub mod chat_attachment;
+pub mod chat_escalation;
+pub mod chat_event;
 pub mod chat_handler;
+pub mod chat_message;
+pub mod chat_repository;
 pub mod chat_room;
 pub mod chat_service;
+pub mod chat_sla;
+pub mod chat_survey;
+pub mod chat_transfer;
+pub mod event_forwarder;
+pub mod queue_status;
+pub mod room_bus;
+pub mod room_store;
+pub mod typing;
 
 #[derive(Debug, Clone, PartialEq, Hash, Eq, Serialize, Deserialize)]
 pub struct ChatRoomId(String);
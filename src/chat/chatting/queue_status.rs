@@ -0,0 +1,77 @@
+use serde::Serialize;
+
+use super::ChatRoomId;
+
+// 대기 중인 고객에게 알려주는 대기 순번과 예상 대기 시간
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueStatus {
+    pub room_id: ChatRoomId,
+    pub queue: String,
+    // 1 부터 시작 (다음 배정 대상이 1)
+    pub position: usize,
+    pub queue_length: usize,
+    pub available_agents: usize,
+    // 최근 배정 기록이 없으면 알 수 없다
+    pub estimated_wait_seconds: Option<i64>,
+}
+
+impl QueueStatus {
+    // 최근 평균 대기 시간을 순번만큼 늘리고, 대기 상담원이 많을수록 나눠서 줄인다
+    pub fn estimate(
+        room_id: ChatRoomId,
+        queue: &str,
+        position: usize,
+        queue_length: usize,
+        available_agents: usize,
+        average_wait_seconds: Option<f64>,
+    ) -> Self {
+        let estimated_wait_seconds = average_wait_seconds.map(|average_wait_seconds| {
+            let agents = available_agents.max(1) as f64;
+            (average_wait_seconds * position as f64 / agents).round() as i64
+        });
+
+        Self {
+            room_id,
+            queue: queue.to_string(),
+            position,
+            queue_length,
+            available_agents,
+            estimated_wait_seconds,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(position: usize, available_agents: usize, average: Option<f64>) -> Option<i64> {
+        QueueStatus::estimate(
+            ChatRoomId("room".to_string()),
+            "general",
+            position,
+            3,
+            available_agents,
+            average,
+        )
+        .estimated_wait_seconds
+    }
+
+    #[test]
+    fn wait_grows_with_position() {
+        assert_eq!(estimate(1, 1, Some(30.0)), Some(30));
+        assert_eq!(estimate(3, 1, Some(30.0)), Some(90));
+    }
+
+    #[test]
+    fn wait_is_shared_by_available_agents() {
+        assert_eq!(estimate(3, 2, Some(30.0)), Some(45));
+        // 대기 상담원이 없어도 한 명이 있는 것처럼 계산한다
+        assert_eq!(estimate(3, 0, Some(30.0)), Some(90));
+    }
+
+    #[test]
+    fn wait_is_unknown_without_history() {
+        assert_eq!(estimate(1, 1, None), None);
+    }
+}
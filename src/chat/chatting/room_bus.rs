@@ -0,0 +1,193 @@
+use std::{fmt, time::Duration};
+
+use futures::StreamExt;
+use redis::{aio::MultiplexedConnection, AsyncCommands, RedisResult};
+use tokio::task::JoinHandle;
+
+use crate::{
+    chat::agent::agent::{AgentNotification, Agents},
+    config::{app_state::SocketRooms, error::AppError, MangJooResult},
+};
+
+use super::{
+    chat_escalation::{SupervisorNotification, SupervisorNotifier},
+    chat_event::ChatEvent,
+    ChatRoomId,
+};
+
+const ROOM_CHANNEL_PREFIX: &str = "chat:room:";
+const AGENT_CHANNEL_PREFIX: &str = "chat:agent:";
+const SUPERVISOR_CHANNEL: &str = "chat:supervisor";
+const RELAY_RETRY_DELAY: Duration = Duration::from_secs(3);
+
+// 방 이벤트와 상담원, 팀장 알림을 Redis 채널로 발행하고, 각 인스턴스가 자신에게 연결된 소켓으로 전달한다
+#[derive(Clone)]
+pub struct RoomBus {
+    client: redis::Client,
+    publisher: MultiplexedConnection,
+}
+
+impl fmt::Debug for RoomBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RoomBus")
+            .field("client", &self.client)
+            .finish()
+    }
+}
+
+impl RoomBus {
+    pub async fn connect(redis_url: &str) -> MangJooResult<Self> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|err| AppError::ConnectionError(format!("Redis Error {}", err)))?;
+        let publisher = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|err| AppError::ConnectionError(format!("Redis Error {}", err)))?;
+
+        Ok(Self { client, publisher })
+    }
+
+    pub async fn publish(&self, room_id: &ChatRoomId, event: &ChatEvent) -> MangJooResult<()> {
+        let payload = event.to_json()?;
+        let mut publisher = self.publisher.clone();
+        publisher
+            .publish::<_, _, ()>(room_channel(room_id), payload)
+            .await
+            .map_err(|err| AppError::ConnectionError(format!("Redis Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn publish_agent(
+        &self,
+        agent_id: i64,
+        notification: &AgentNotification,
+    ) -> MangJooResult<()> {
+        let payload = serde_json::to_string(notification)
+            .map_err(|err| AppError::InternalError(format!("Serialize error {}", err)))?;
+        let mut publisher = self.publisher.clone();
+        publisher
+            .publish::<_, _, ()>(format!("{}{}", AGENT_CHANNEL_PREFIX, agent_id), payload)
+            .await
+            .map_err(|err| AppError::ConnectionError(format!("Redis Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn publish_supervisors(
+        &self,
+        notification: &SupervisorNotification,
+    ) -> MangJooResult<()> {
+        let payload = serde_json::to_string(notification)
+            .map_err(|err| AppError::InternalError(format!("Serialize error {}", err)))?;
+        let mut publisher = self.publisher.clone();
+        publisher
+            .publish::<_, _, ()>(SUPERVISOR_CHANNEL, payload)
+            .await
+            .map_err(|err| AppError::ConnectionError(format!("Redis Error {}", err)))?;
+
+        Ok(())
+    }
+
+    // 구독이 끊기면 잠시 후 다시 구독한다
+    pub fn start_relay(
+        &self,
+        socket_rooms: SocketRooms,
+        agents: Agents,
+        supervisors: SupervisorNotifier,
+    ) -> JoinHandle<()> {
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = relay(&client, &socket_rooms, &agents, &supervisors).await {
+                    tracing::error!("Room relay disconnected {:?}", err);
+                }
+                tokio::time::sleep(RELAY_RETRY_DELAY).await;
+            }
+        })
+    }
+}
+
+async fn relay(
+    client: &redis::Client,
+    socket_rooms: &SocketRooms,
+    agents: &Agents,
+    supervisors: &SupervisorNotifier,
+) -> RedisResult<()> {
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub
+        .psubscribe(format!("{}*", ROOM_CHANNEL_PREFIX))
+        .await?;
+    pubsub
+        .psubscribe(format!("{}*", AGENT_CHANNEL_PREFIX))
+        .await?;
+    pubsub.subscribe(SUPERVISOR_CHANNEL).await?;
+    tracing::info!("Room relay subscribed");
+
+    let mut messages = pubsub.on_message();
+    while let Some(message) = messages.next().await {
+        let payload: String = match message.get_payload() {
+            Ok(payload) => payload,
+            Err(err) => {
+                tracing::warn!("Invalid relay payload {:?}", err);
+                continue;
+            }
+        };
+
+        let channel = message.get_channel_name();
+        if channel == SUPERVISOR_CHANNEL {
+            relay_supervisor_notification(supervisors, &payload);
+            continue;
+        }
+        if let Some(agent_id) = channel.strip_prefix(AGENT_CHANNEL_PREFIX) {
+            relay_agent_notification(agents, agent_id, &payload).await;
+            continue;
+        }
+        let Some(room_id) = channel.strip_prefix(ROOM_CHANNEL_PREFIX) else {
+            continue;
+        };
+
+        let event: ChatEvent = match serde_json::from_str(&payload) {
+            Ok(event) => event,
+            Err(err) => {
+                tracing::warn!("Invalid room event {:?}", err);
+                continue;
+            }
+        };
+
+        // 이 인스턴스에 연결된 참여자가 없는 방은 무시한다
+        let room_id = ChatRoomId(room_id.to_string());
+        if let Some(tx) = socket_rooms.read().await.get(&room_id) {
+            let _ = tx.send(event);
+        }
+    }
+
+    Ok(())
+}
+
+// 상태 소켓이 이 인스턴스에 연결된 상담원에게만 전달된다
+async fn relay_agent_notification(agents: &Agents, agent_id: &str, payload: &str) {
+    let Ok(agent_id) = agent_id.parse::<i64>() else {
+        return;
+    };
+    match serde_json::from_str::<AgentNotification>(payload) {
+        Ok(notification) => {
+            agents.notify(agent_id, notification).await;
+        }
+        Err(err) => tracing::warn!("Invalid agent notification {:?}", err),
+    }
+}
+
+// 팀장 알림 소켓이 이 인스턴스에 연결된 팀장에게만 전달된다
+fn relay_supervisor_notification(supervisors: &SupervisorNotifier, payload: &str) {
+    match serde_json::from_str::<SupervisorNotification>(payload) {
+        Ok(notification) => {
+            supervisors.notify(notification);
+        }
+        Err(err) => tracing::warn!("Invalid supervisor notification {:?}", err),
+    }
+}
+
+fn room_channel(room_id: &ChatRoomId) -> String {
+    format!("{}{}", ROOM_CHANNEL_PREFIX, room_id.0)
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use redis::{aio::MultiplexedConnection, AsyncCommands};
+use tokio::sync::RwLock;
+
+use crate::config::{
+    redis_json::{redis_error, RedisJsonMap},
+    MangJooResult,
+};
+
+use super::{chat_room::ChatRoom, ChatRoomId};
+
+pub type RoomUpdate<'a> = &'a (dyn Fn(&mut ChatRoom) -> MangJooResult<()> + Send + Sync);
+
+// 진행 중인 방과 대기열 저장소 (단일 인스턴스는 메모리, 다중 인스턴스는 Redis)
+#[async_trait]
+pub trait RoomStore: Send + Sync + std::fmt::Debug {
+    async fn get(&self, room_id: &ChatRoomId) -> MangJooResult<Option<ChatRoom>>;
+
+    async fn insert(&self, room: &ChatRoom) -> MangJooResult<()>;
+
+    // 이미 있는 방이면 덮어쓰지 않고 false 를 돌려준다
+    async fn insert_if_absent(&self, room: &ChatRoom) -> MangJooResult<bool>;
+
+    // 갱신 함수가 에러를 돌려주면 저장하지 않는다
+    async fn update(
+        &self,
+        room_id: &ChatRoomId,
+        apply: RoomUpdate<'_>,
+    ) -> MangJooResult<Option<ChatRoom>>;
+
+    async fn remove(&self, room_id: &ChatRoomId) -> MangJooResult<Option<ChatRoom>>;
+
+    async fn list(&self) -> MangJooResult<Vec<ChatRoom>>;
+
+    // score 가 작을수록 대기열 앞에 온다 (queue_score 참고)
+    async fn enqueue(&self, queue: &str, room_id: &ChatRoomId, score: i64) -> MangJooResult<()>;
+
+    // 대기열에서 빼낸 경우에만 true 를 돌려준다
+    async fn take_waiting(&self, queue: &str, room_id: &ChatRoomId) -> MangJooResult<bool>;
+
+    // 대기열 전체 (맨 앞이 다음 배정 대상)
+    async fn waiting_rooms(&self, queue: &str) -> MangJooResult<Vec<ChatRoomId>>;
+}
+
+// 우선순위 한 단계가 대기 시간(ms)보다 항상 크도록 둔다
+const PRIORITY_WEIGHT: i64 = 10_000_000_000_000;
+
+// 우선순위가 높을수록, 같은 우선순위에서는 먼저 들어온 방이 앞에 온다
+// (queued_at_millis 를 0 으로 주면 같은 우선순위의 맨 앞에 들어간다)
+pub fn queue_score(priority: i32, queued_at_millis: i64) -> i64 {
+    queued_at_millis - i64::from(priority) * PRIORITY_WEIGHT
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryRoomStore {
+    rooms: RwLock<HashMap<ChatRoomId, ChatRoom>>,
+    waiting_queues: RwLock<HashMap<String, Vec<(i64, ChatRoomId)>>>,
+}
+
+impl InMemoryRoomStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RoomStore for InMemoryRoomStore {
+    async fn get(&self, room_id: &ChatRoomId) -> MangJooResult<Option<ChatRoom>> {
+        Ok(self.rooms.read().await.get(room_id).cloned())
+    }
+
+    async fn insert(&self, room: &ChatRoom) -> MangJooResult<()> {
+        let mut rooms = self.rooms.write().await;
+        rooms.insert(room.room_id.clone(), room.clone());
+        Ok(())
+    }
+
+    async fn insert_if_absent(&self, room: &ChatRoom) -> MangJooResult<bool> {
+        let mut rooms = self.rooms.write().await;
+        if rooms.contains_key(&room.room_id) {
+            return Ok(false);
+        }
+        rooms.insert(room.room_id.clone(), room.clone());
+        Ok(true)
+    }
+
+    async fn update(
+        &self,
+        room_id: &ChatRoomId,
+        apply: RoomUpdate<'_>,
+    ) -> MangJooResult<Option<ChatRoom>> {
+        let mut rooms = self.rooms.write().await;
+        let Some(room) = rooms.get_mut(room_id) else {
+            return Ok(None);
+        };
+
+        let mut updated = room.clone();
+        apply(&mut updated)?;
+        *room = updated.clone();
+
+        Ok(Some(updated))
+    }
+
+    async fn remove(&self, room_id: &ChatRoomId) -> MangJooResult<Option<ChatRoom>> {
+        Ok(self.rooms.write().await.remove(room_id))
+    }
+
+    async fn list(&self) -> MangJooResult<Vec<ChatRoom>> {
+        Ok(self.rooms.read().await.values().cloned().collect())
+    }
+
+    async fn enqueue(&self, queue: &str, room_id: &ChatRoomId, score: i64) -> MangJooResult<()> {
+        let mut waiting_queues = self.waiting_queues.write().await;
+        let waiting_queue = waiting_queues.entry(queue.to_string()).or_default();
+        waiting_queue.retain(|(_, id)| id != room_id);
+        let index = waiting_queue.partition_point(|(queued_score, _)| *queued_score <= score);
+        waiting_queue.insert(index, (score, room_id.clone()));
+        Ok(())
+    }
+
+    async fn take_waiting(&self, queue: &str, room_id: &ChatRoomId) -> MangJooResult<bool> {
+        let mut waiting_queues = self.waiting_queues.write().await;
+        let Some(waiting_queue) = waiting_queues.get_mut(queue) else {
+            return Ok(false);
+        };
+        let before = waiting_queue.len();
+        waiting_queue.retain(|(_, id)| id != room_id);
+        Ok(waiting_queue.len() < before)
+    }
+
+    async fn waiting_rooms(&self, queue: &str) -> MangJooResult<Vec<ChatRoomId>> {
+        let waiting_queues = self.waiting_queues.read().await;
+        Ok(waiting_queues
+            .get(queue)
+            .map(|waiting_queue| waiting_queue.iter().map(|(_, id)| id.clone()).collect())
+            .unwrap_or_default())
+    }
+}
+
+const ROOM_KEY_PREFIX: &str = "chat:state:room:";
+const ROOM_INDEX_KEY: &str = "chat:state:rooms";
+const WAITING_QUEUE_KEY_PREFIX: &str = "chat:state:waiting:";
+
+#[derive(Debug, Clone)]
+pub struct RedisRoomStore {
+    rooms: RedisJsonMap,
+    connection: MultiplexedConnection,
+}
+
+impl RedisRoomStore {
+    pub fn new(connection: MultiplexedConnection) -> Self {
+        Self {
+            rooms: RedisJsonMap::new(connection.clone(), ROOM_KEY_PREFIX, ROOM_INDEX_KEY),
+            connection,
+        }
+    }
+}
+
+#[async_trait]
+impl RoomStore for RedisRoomStore {
+    async fn get(&self, room_id: &ChatRoomId) -> MangJooResult<Option<ChatRoom>> {
+        self.rooms.get(&room_id.0).await
+    }
+
+    async fn insert(&self, room: &ChatRoom) -> MangJooResult<()> {
+        self.rooms.insert(&room.room_id.0, room).await
+    }
+
+    async fn insert_if_absent(&self, room: &ChatRoom) -> MangJooResult<bool> {
+        self.rooms.insert_if_absent(&room.room_id.0, room).await
+    }
+
+    async fn update(
+        &self,
+        room_id: &ChatRoomId,
+        apply: RoomUpdate<'_>,
+    ) -> MangJooResult<Option<ChatRoom>> {
+        self.rooms.update(&room_id.0, apply).await
+    }
+
+    async fn remove(&self, room_id: &ChatRoomId) -> MangJooResult<Option<ChatRoom>> {
+        self.rooms.remove(&room_id.0).await
+    }
+
+    async fn list(&self) -> MangJooResult<Vec<ChatRoom>> {
+        self.rooms.values().await
+    }
+
+    async fn enqueue(&self, queue: &str, room_id: &ChatRoomId, score: i64) -> MangJooResult<()> {
+        let mut connection = self.connection.clone();
+        connection
+            .zadd::<_, _, _, ()>(waiting_queue_key(queue), &room_id.0, score as f64)
+            .await
+            .map_err(redis_error)
+    }
+
+    // ZREM 은 원자적이므로 같은 방을 두 인스턴스가 함께 꺼내지 않는다
+    async fn take_waiting(&self, queue: &str, room_id: &ChatRoomId) -> MangJooResult<bool> {
+        let mut connection = self.connection.clone();
+        let removed: i64 = connection
+            .zrem(waiting_queue_key(queue), &room_id.0)
+            .await
+            .map_err(redis_error)?;
+
+        Ok(removed > 0)
+    }
+
+    async fn waiting_rooms(&self, queue: &str) -> MangJooResult<Vec<ChatRoomId>> {
+        let mut connection = self.connection.clone();
+        let room_ids: Vec<String> = connection
+            .zrange(waiting_queue_key(queue), 0, -1)
+            .await
+            .map_err(redis_error)?;
+
+        Ok(room_ids.into_iter().map(ChatRoomId).collect())
+    }
+}
+
+fn waiting_queue_key(queue: &str) -> String {
+    format!("{}{}", WAITING_QUEUE_KEY_PREFIX, queue)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chat::customer::customer_profile::CustomerTier;
+
+    use super::*;
+
+    const QUEUE: &str = "general";
+
+    fn room_id(room_id: &str) -> ChatRoomId {
+        ChatRoomId(room_id.to_string())
+    }
+
+    #[test]
+    fn higher_tier_is_ahead_of_earlier_rooms() {
+        let standard = queue_score(CustomerTier::Standard.priority(), 1_000);
+        let gold = queue_score(CustomerTier::Gold.priority(), 2_000);
+        let vip = queue_score(CustomerTier::Vip.priority(), 3_000);
+
+        assert!(vip < gold);
+        assert!(gold < standard);
+    }
+
+    #[test]
+    fn same_tier_keeps_arrival_order() {
+        let priority = CustomerTier::Gold.priority();
+
+        assert!(queue_score(priority, 1_000) < queue_score(priority, 2_000));
+        // 되돌린 방은 같은 우선순위의 맨 앞으로 간다
+        assert!(queue_score(priority, 0) < queue_score(priority, 1_000));
+        assert!(queue_score(priority, 0) > queue_score(CustomerTier::Vip.priority(), 1_000));
+    }
+
+    #[tokio::test]
+    async fn waiting_rooms_are_ordered_by_score() {
+        let store = InMemoryRoomStore::new();
+        let rooms = [
+            ("standard", CustomerTier::Standard, 1_000),
+            ("vip", CustomerTier::Vip, 3_000),
+            ("gold", CustomerTier::Gold, 2_000),
+            ("later-vip", CustomerTier::Vip, 4_000),
+        ];
+        for (id, tier, queued_at) in rooms {
+            let score = queue_score(tier.priority(), queued_at);
+            store.enqueue(QUEUE, &room_id(id), score).await.unwrap();
+        }
+
+        assert_eq!(
+            store.waiting_rooms(QUEUE).await.unwrap(),
+            vec![
+                room_id("vip"),
+                room_id("later-vip"),
+                room_id("gold"),
+                room_id("standard")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn enqueue_again_moves_the_room() {
+        let store = InMemoryRoomStore::new();
+        store.enqueue(QUEUE, &room_id("first"), 1).await.unwrap();
+        store.enqueue(QUEUE, &room_id("second"), 2).await.unwrap();
+
+        store.enqueue(QUEUE, &room_id("second"), 0).await.unwrap();
+
+        assert_eq!(
+            store.waiting_rooms(QUEUE).await.unwrap(),
+            vec![room_id("second"), room_id("first")]
+        );
+        assert!(store.take_waiting(QUEUE, &room_id("first")).await.unwrap());
+        assert!(!store.take_waiting(QUEUE, &room_id("first")).await.unwrap());
+    }
+}
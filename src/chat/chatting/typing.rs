@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+// 같은 입력 중 상태는 이 간격보다 자주 보내지 않는다
+const TYPING_THROTTLE: Duration = Duration::from_secs(3);
+// 입력 중 이벤트가 이 시간 동안 없으면 입력을 멈춘 것으로 본다
+const TYPING_EXPIRY: Duration = Duration::from_secs(5);
+
+// 연결 하나의 입력 중 상태 (브로드캐스트 여부를 결정한다)
+#[derive(Debug, Default)]
+pub struct TypingState {
+    is_typing: bool,
+    last_broadcast: Option<Instant>,
+    last_activity: Option<Instant>,
+}
+
+impl TypingState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_typing(&self) -> bool {
+        self.is_typing
+    }
+
+    // 브로드캐스트할 상태를 돌려준다 (None 이면 무시)
+    pub fn update(&mut self, is_typing: bool, now: Instant) -> Option<bool> {
+        if !is_typing {
+            return self.stop();
+        }
+
+        self.last_activity = Some(now);
+        let throttled = self.is_typing
+            && self
+                .last_broadcast
+                .is_some_and(|last_broadcast| now - last_broadcast < TYPING_THROTTLE);
+        if throttled {
+            return None;
+        }
+
+        self.is_typing = true;
+        self.last_broadcast = Some(now);
+        Some(true)
+    }
+
+    // 메시지를 보내거나 입력이 만료되면 입력 중 상태를 해제한다
+    pub fn stop(&mut self) -> Option<bool> {
+        if !self.is_typing {
+            return None;
+        }
+
+        self.is_typing = false;
+        self.last_broadcast = None;
+        Some(false)
+    }
+
+    pub fn expires_at(&self) -> Instant {
+        self.last_activity.unwrap_or_else(Instant::now) + TYPING_EXPIRY
+    }
+}
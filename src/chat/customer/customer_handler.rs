@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chat::chatting::chat_room::ChatRoom,
+    config::{
+        app_state::ArcAppState,
+        session::{AuthUser, RequiredAdmin, RequiredAgent},
+        MangJooResult,
+    },
+};
+
+use super::{
+    customer_profile::{Customer, CustomerProfile, CustomerTier, CustomerView},
+    customer_service,
+};
+
+#[derive(Debug, Serialize)]
+pub struct CustomerRoomsResponse {
+    rooms: Vec<ChatRoom>,
+}
+
+#[tracing::instrument]
+pub async fn find_customer(
+    State(app_state): State<ArcAppState>,
+    Path(customer_id): Path<i64>,
+    AuthUser(user_session): AuthUser,
+) -> MangJooResult<Json<CustomerView>> {
+    customer_service::find_customer_view(customer_id, &user_session, &app_state.customers)
+        .await
+        .map(Json)
+}
+
+#[tracing::instrument]
+pub async fn find_customer_rooms(
+    State(app_state): State<ArcAppState>,
+    Path(customer_id): Path<i64>,
+    AuthUser(user_session): AuthUser,
+) -> MangJooResult<Json<CustomerRoomsResponse>> {
+    let rooms = customer_service::find_customer_rooms(
+        customer_id,
+        &user_session,
+        &app_state.customers,
+        &app_state.rooms,
+    )
+    .await?;
+
+    Ok(Json(CustomerRoomsResponse { rooms }))
+}
+
+#[tracing::instrument]
+pub async fn update_customer(
+    State(app_state): State<ArcAppState>,
+    Path(customer_id): Path<i64>,
+    RequiredAgent(_session): RequiredAgent,
+    Json(profile): Json<CustomerProfile>,
+) -> MangJooResult<Json<Customer>> {
+    customer_service::update_customer_profile(customer_id, profile, &app_state.customers)
+        .await
+        .map(Json)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCustomerTierRequest {
+    tier: CustomerTier,
+}
+
+// 등급은 관리자만 바꿀 수 있다
+#[tracing::instrument]
+pub async fn update_customer_tier(
+    State(app_state): State<ArcAppState>,
+    Path(customer_id): Path<i64>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Json(request): Json<UpdateCustomerTierRequest>,
+) -> MangJooResult<Json<Customer>> {
+    customer_service::update_customer_tier(customer_id, request.tier, &app_state.customers)
+        .await
+        .map(Json)
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 고객 정보 (이름, 이메일은 users 테이블 기준)
+#[derive(Debug, Clone, Serialize)]
+pub struct Customer {
+    pub customer_id: i64,
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub locale: Option<String>,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub tier: CustomerTier,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// 고객 본인이 보는 정보 (상담원용 태그와 메모는 뺀다)
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnCustomer {
+    pub customer_id: i64,
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub locale: Option<String>,
+    pub tier: CustomerTier,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<Customer> for OwnCustomer {
+    fn from(customer: Customer) -> Self {
+        Self {
+            customer_id: customer.customer_id,
+            name: customer.name,
+            email: customer.email,
+            phone: customer.phone,
+            locale: customer.locale,
+            tier: customer.tier,
+            updated_at: customer.updated_at,
+        }
+    }
+}
+
+// 조회한 사람에 따라 달라지는 고객 정보
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum CustomerView {
+    Staff(Customer),
+    Own(OwnCustomer),
+}
+
+// 고객 등급 (높은 등급은 같은 대기열에서 먼저 배정된다)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomerTier {
+    #[default]
+    Standard,
+    Gold,
+    Vip,
+}
+
+impl CustomerTier {
+    // 방의 대기 우선순위
+    pub fn priority(&self) -> i32 {
+        match self {
+            CustomerTier::Standard => 0,
+            CustomerTier::Gold => 1,
+            CustomerTier::Vip => 2,
+        }
+    }
+}
+
+impl From<String> for CustomerTier {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "gold" => Self::Gold,
+            "vip" => Self::Vip,
+            _ => Self::Standard,
+        }
+    }
+}
+
+impl fmt::Display for CustomerTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CustomerTier::Standard => write!(f, "standard"),
+            CustomerTier::Gold => write!(f, "gold"),
+            CustomerTier::Vip => write!(f, "vip"),
+        }
+    }
+}
+
+// 상담원이 수정할 수 있는 고객 정보
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomerProfile {
+    pub phone: Option<String>,
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::config::{error::AppError, MangJooResult};
+
+use super::customer_profile::{Customer, CustomerProfile, CustomerTier};
+
+#[derive(Debug, Clone)]
+pub struct CustomerRepository {
+    pool: PgPool,
+}
+
+impl CustomerRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // 프로필이 아직 없는 고객도 기본 정보로 조회된다
+    pub async fn find_by_id(&self, customer_id: i64) -> MangJooResult<Option<Customer>> {
+        let entity = sqlx::query_as!(
+            CustomerEntity,
+            r#"SELECT u.user_id AS customer_id, u.name, u.email,
+                c.phone AS "phone?", c.locale AS "locale?",
+                COALESCE(c.tags, '{}') AS "tags!", c.notes AS "notes?",
+                COALESCE(c.tier, 'standard') AS "tier!",
+                c.updated_at AS "updated_at?"
+            FROM users u
+            LEFT JOIN customers c ON c.customer_id = u.user_id
+            WHERE u.user_id = $1 AND u.role = 'user' AND u.deleted = FALSE
+            "#,
+            customer_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(Customer::from))
+    }
+
+    pub async fn save_profile(
+        &self,
+        customer_id: i64,
+        profile: &CustomerProfile,
+    ) -> MangJooResult<()> {
+        sqlx::query!(
+            "INSERT INTO customers (customer_id, phone, locale, tags, notes)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (customer_id)
+            DO UPDATE SET phone = $2, locale = $3, tags = $4, notes = $5, updated_at = NOW()
+            ",
+            customer_id,
+            profile.phone,
+            profile.locale,
+            &profile.tags,
+            profile.notes
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn save_tier(&self, customer_id: i64, tier: CustomerTier) -> MangJooResult<()> {
+        sqlx::query!(
+            "INSERT INTO customers (customer_id, tier)
+            VALUES ($1, $2)
+            ON CONFLICT (customer_id)
+            DO UPDATE SET tier = $2, updated_at = NOW()
+            ",
+            customer_id,
+            tier.to_string()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+
+    // 프로필이 없는 고객은 기본 등급
+    pub async fn find_tier(&self, customer_id: i64) -> MangJooResult<CustomerTier> {
+        let tier = sqlx::query_scalar!(
+            "SELECT tier
+            FROM customers
+            WHERE customer_id = $1
+            ",
+            customer_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(tier.map(CustomerTier::from).unwrap_or_default())
+    }
+}
+
+#[derive(Debug)]
+pub struct CustomerEntity {
+    customer_id: i64,
+    name: String,
+    email: String,
+    phone: Option<String>,
+    locale: Option<String>,
+    tags: Vec<String>,
+    notes: Option<String>,
+    tier: String,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<CustomerEntity> for Customer {
+    fn from(entity: CustomerEntity) -> Self {
+        Customer {
+            customer_id: entity.customer_id,
+            name: entity.name,
+            email: entity.email,
+            phone: entity.phone,
+            locale: entity.locale,
+            tags: entity.tags,
+            notes: entity.notes,
+            tier: CustomerTier::from(entity.tier),
+            updated_at: entity.updated_at,
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    chat::chatting::chat_room::{ChatRoom, ChatRooms},
+    config::{error::AppError, session::UserSession, MangJooResult},
+};
+
+use super::{
+    customer_profile::{Customer, CustomerProfile, CustomerTier, CustomerView},
+    customer_repository::CustomerRepository,
+};
+
+const MAX_TAGS: usize = 20;
+const MAX_TAG_LENGTH: usize = 50;
+const MAX_NOTES_LENGTH: usize = 2000;
+
+pub async fn find_customer(
+    customer_id: i64,
+    user_session: &UserSession,
+    customers: &CustomerRepository,
+) -> MangJooResult<Customer> {
+    check_access(customer_id, user_session)?;
+
+    customers
+        .find_by_id(customer_id)
+        .await?
+        .ok_or_else(|| AppError::CustomerNotFound(format!("Customer Id = {}", customer_id)))
+}
+
+// 고객 본인에게는 상담원용 태그와 메모를 보여주지 않는다
+pub async fn find_customer_view(
+    customer_id: i64,
+    user_session: &UserSession,
+    customers: &CustomerRepository,
+) -> MangJooResult<CustomerView> {
+    let customer = find_customer(customer_id, user_session, customers).await?;
+
+    Ok(customer_view(customer, user_session))
+}
+
+fn customer_view(customer: Customer, user_session: &UserSession) -> CustomerView {
+    if user_session.is_staff() {
+        CustomerView::Staff(customer)
+    } else {
+        CustomerView::Own(customer.into())
+    }
+}
+
+// 고객의 모든 상담 이력 (최신순)
+pub async fn find_customer_rooms(
+    customer_id: i64,
+    user_session: &UserSession,
+    customers: &CustomerRepository,
+    chat_rooms: &ChatRooms,
+) -> MangJooResult<Vec<ChatRoom>> {
+    find_customer(customer_id, user_session, customers).await?;
+
+    chat_rooms.find_rooms_by_customer(customer_id).await
+}
+
+pub async fn update_customer_profile(
+    customer_id: i64,
+    mut profile: CustomerProfile,
+    customers: &CustomerRepository,
+) -> MangJooResult<Customer> {
+    customers
+        .find_by_id(customer_id)
+        .await?
+        .ok_or_else(|| AppError::CustomerNotFound(format!("Customer Id = {}", customer_id)))?;
+
+    // 태그는 앞뒤 공백을 제거하고 중복 없이 저장한다
+    let mut tags: Vec<String> = Vec::new();
+    for tag in profile.tags.iter().map(|tag| tag.trim()) {
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(AppError::InvalidRequest(format!(
+                "Tag must be 1 to {} characters",
+                MAX_TAG_LENGTH
+            )));
+        }
+        if !tags.iter().any(|saved| saved == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    if tags.len() > MAX_TAGS {
+        return Err(AppError::InvalidRequest(format!(
+            "Customer can have at most {} tags",
+            MAX_TAGS
+        )));
+    }
+    profile.tags = tags;
+
+    if profile
+        .notes
+        .as_ref()
+        .is_some_and(|notes| notes.chars().count() > MAX_NOTES_LENGTH)
+    {
+        return Err(AppError::InvalidRequest(format!(
+            "Notes must be at most {} characters",
+            MAX_NOTES_LENGTH
+        )));
+    }
+
+    customers.save_profile(customer_id, &profile).await?;
+
+    customers
+        .find_by_id(customer_id)
+        .await?
+        .ok_or_else(|| AppError::CustomerNotFound(format!("Customer Id = {}", customer_id)))
+}
+
+pub async fn update_customer_tier(
+    customer_id: i64,
+    tier: CustomerTier,
+    customers: &CustomerRepository,
+) -> MangJooResult<Customer> {
+    customers
+        .find_by_id(customer_id)
+        .await?
+        .ok_or_else(|| AppError::CustomerNotFound(format!("Customer Id = {}", customer_id)))?;
+
+    customers.save_tier(customer_id, tier).await?;
+
+    customers
+        .find_by_id(customer_id)
+        .await?
+        .ok_or_else(|| AppError::CustomerNotFound(format!("Customer Id = {}", customer_id)))
+}
+
+// 상담원, 팀장, 관리자는 모든 고객을, 고객은 본인 정보만 조회 가능
+fn check_access(customer_id: i64, user_session: &UserSession) -> MangJooResult<()> {
+    if user_session.is_staff() || user_session.user_id == customer_id {
+        return Ok(());
+    }
+
+    Err(AppError::Unauthorized(
+        "Not allowed to view this customer".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{config::test_support::session, user::user::UserRole};
+
+    use super::*;
+
+    fn customer() -> Customer {
+        Customer {
+            customer_id: 1,
+            name: "customer".to_string(),
+            email: "customer@example.com".to_string(),
+            phone: Some("010-0000-0000".to_string()),
+            locale: Some("ko".to_string()),
+            tags: vec!["refund".to_string()],
+            notes: Some("Asked for a refund twice".to_string()),
+            tier: CustomerTier::Gold,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn customer_does_not_see_internal_notes_and_tags() {
+        let view = customer_view(customer(), &session(1, UserRole::User));
+
+        let json = serde_json::to_value(view).unwrap();
+        assert_eq!(json["customer_id"], 1);
+        assert_eq!(json["phone"], "010-0000-0000");
+        assert!(json.get("notes").is_none());
+        assert!(json.get("tags").is_none());
+    }
+
+    #[test]
+    fn staff_see_internal_notes_and_tags() {
+        for role in [UserRole::Agent, UserRole::Supervisor, UserRole::Admin] {
+            let view = customer_view(customer(), &session(2, role));
+
+            let json = serde_json::to_value(view).unwrap();
+            assert_eq!(json["notes"], "Asked for a refund twice");
+            assert_eq!(json["tags"][0], "refund");
+        }
+    }
+}
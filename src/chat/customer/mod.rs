@@ -1 +1,4 @@
-
+pub mod customer_handler;
+pub mod customer_profile;
+pub mod customer_repository;
+pub mod customer_service;
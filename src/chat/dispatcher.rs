@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+
+use crate::{
+    config::{app_state::ArcAppState, error::AppError},
+    user::user::UserRole,
+};
+
+use super::{
+    agent::{agent::AgentNotification, routing_strategy::RoutingContext},
+    chatting::chat_event::ChatEvent,
+};
+
+// 상담원 상태 변화는 알림 없이도 주기적으로 다시 확인한다
+const DISPATCH_INTERVAL: Duration = Duration::from_secs(3);
+
+// 우선순위가 높은 대기열부터, 대기열 안에서는 고객 등급과 들어온 순서대로
+// 스킬이 맞는 상담원에게 배정한다
+pub fn start_dispatcher(state: ArcAppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DISPATCH_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = state.dispatch_notify.notified() => {},
+                _ = interval.tick() => {},
+            }
+            dispatch_waiting_rooms(&state).await;
+        }
+    })
+}
+
+async fn dispatch_waiting_rooms(state: &ArcAppState) {
+    let queues = match state.chat_queues.find_all().await {
+        Ok(queues) => queues,
+        Err(err) => {
+            tracing::error!("Can't load chat queues {:?}", err);
+            return;
+        }
+    };
+
+    for queue in queues {
+        dispatch_queue(state, &queue.name).await;
+    }
+}
+
+async fn dispatch_queue(state: &ArcAppState, queue: &str) {
+    let waiting_rooms = match state.rooms.waiting_rooms(queue).await {
+        Ok(waiting_rooms) => waiting_rooms,
+        Err(err) => {
+            tracing::error!("Can't load waiting queue {} {:?}", queue, err);
+            return;
+        }
+    };
+
+    for room_id in waiting_rooms {
+        // 대기열을 구독한 상담원만 배정 대상이다
+        let agents: Vec<_> = match state.agents.find_assignable_agents().await {
+            Ok(agents) => agents
+                .into_iter()
+                .filter(|(_, agent)| agent.serves_queue(queue))
+                .collect(),
+            Err(err) => {
+                tracing::error!("Can't load agents {:?}", err);
+                return;
+            }
+        };
+        if agents.is_empty() {
+            return;
+        }
+
+        let room = match state.rooms.find_room(&room_id).await {
+            Ok(Some(room)) => room,
+            Ok(None) => continue,
+            Err(err) => {
+                tracing::error!("Can't load chat room {:?}", err);
+                continue;
+            }
+        };
+        let context = if state.router.strategy(queue).uses_previous_agent() {
+            RoutingContext {
+                previous_agent_id: state.rooms.find_previous_agent(&room).await,
+            }
+        } else {
+            RoutingContext::default()
+        };
+        // 맞는 상담원이 없으면 다음 방을 먼저 배정한다
+        let Some(agent_id) = state.router.select_agent(queue, &room, &agents, &context) else {
+            continue;
+        };
+
+        // 대기열에서 빼는 것은 원자적이므로 다른 인스턴스와 같은 방을 배정하지 않는다
+        match state.rooms.take_waiting(queue, &room_id).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(err) => {
+                tracing::error!("Can't take waiting room {:?}", err);
+                return;
+            }
+        }
+
+        // 다른 인스턴스가 먼저 상담원을 채웠다면 방을 되돌리고 다시 찾는다
+        if let Err(err) = state
+            .agents
+            .try_assign_room(agent_id, room_id.clone())
+            .await
+        {
+            tracing::info!("Agent {} is no longer available {:?}", agent_id, err);
+            if let Err(err) = state.rooms.requeue(&room_id).await {
+                tracing::error!("Can't requeue chat room {:?}", err);
+                return;
+            }
+            continue;
+        }
+
+        // 이미 배정되었거나 종료된 방은 대기열에서 빠지고, 그 밖의 실패는 방을 대기열로 되돌린다
+        // DB 기록만 실패했다면 저장소에는 배정되어 있으므로 그대로 진행한다
+        if let Err(err) = state
+            .rooms
+            .enter_room(UserRole::Agent, room_id.clone(), agent_id)
+            .await
+        {
+            if !state.rooms.is_assigned_to(&room_id, agent_id).await {
+                tracing::warn!("Skip dispatching room {:?} {:?}", room_id, err);
+                state.agents.release_room(agent_id, &room_id).await;
+                if matches!(err, AppError::InvalidRequest(_)) {
+                    continue;
+                }
+                if let Err(err) = state.rooms.requeue(&room_id).await {
+                    tracing::error!("Can't requeue chat room {:?}", err);
+                    return;
+                }
+                continue;
+            }
+            tracing::error!("Room {:?} assigned but not persisted {:?}", room_id, err);
+        }
+
+        tracing::info!("Room {:?} assigned to agent {}", room_id, agent_id);
+
+        let notification = AgentNotification::RoomAssigned {
+            room_id: room_id.clone(),
+            customer_id: room.customer_id,
+            assigned_at: Utc::now(),
+        };
+        state.notify_agent(agent_id, notification).await;
+
+        state
+            .broadcast(&room_id, ChatEvent::system("An agent has been assigned"))
+            .await;
+    }
+}
@@ -1,19 +1,104 @@
 use std::sync::Arc;
 
+use agent::{
+    agent_handler::{
+        find_agents, find_me, update_agent_capacity, update_agent_queues, update_agent_skills,
+        update_agent_status,
+    },
+    presence::agent_presence,
+};
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post, put},
     Router,
 };
-use chatting::chat_handler::{create_room, join_chat_room};
+use chatting::{
+    chat_attachment::MAX_ATTACHMENT_BYTES,
+    chat_handler::{
+        claim_escalation, create_room, download_attachment, find_active_rooms, find_escalations,
+        find_messages, find_queue_status, find_room_sla, find_sla_breaches, find_transfers,
+        join_chat_room, submit_survey, supervisor_notifications, transfer_room, upload_attachment,
+    },
+};
+use customer::customer_handler::{
+    find_customer, find_customer_rooms, update_customer, update_customer_tier,
+};
+use offline::offline_handler::{find_pending_messages, pick_message};
+use queue::queue_handler::{
+    create_holiday, create_queue, delete_holiday, find_business_hours, find_holidays,
+    find_queue_availability, find_queues, find_sla_targets, update_business_hours, update_queue,
+    update_sla_targets,
+};
 
 use crate::config::app_state::AppState;
 
 pub mod agent;
 pub mod chatting;
 pub mod customer;
+pub mod dispatcher;
+pub mod offline;
+pub mod queue;
+pub mod queue_notifier;
+pub mod routing;
+pub mod sla_monitor;
 
 pub async fn create_chat_router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/create/chat-room", post(create_room))
         .route("/join/chat-room/{room_id}", get(join_chat_room))
+        .route("/chat-room/{room_id}/messages", get(find_messages))
+        .route(
+            "/chat-room/{room_id}/attachments",
+            // multipart 헤더 등을 위한 여유분 포함
+            post(upload_attachment).layer(DefaultBodyLimit::max(MAX_ATTACHMENT_BYTES + 64 * 1024)),
+        )
+        .route("/attachments/{attachment_id}", get(download_attachment))
+        .route("/chat-room/{room_id}/survey", post(submit_survey))
+        .route("/chat-room/{room_id}/transfer", post(transfer_room))
+        .route("/chat-room/{room_id}/transfers", get(find_transfers))
+        .route("/chat-room/{room_id}/queue", get(find_queue_status))
+        .route("/chat-room/{room_id}/sla", get(find_room_sla))
+        .route("/supervisor/rooms", get(find_active_rooms))
+        .route("/supervisor/sla/breaches", get(find_sla_breaches))
+        .route("/supervisor/escalations", get(find_escalations))
+        .route(
+            "/supervisor/escalations/{transfer_id}/claim",
+            post(claim_escalation),
+        )
+        .route("/supervisor/ws", get(supervisor_notifications))
+        .route("/agent/ws", get(agent_presence))
+        .route("/agent/status", put(update_agent_status))
+        .route("/agent/me", get(find_me))
+        .route("/agents", get(find_agents))
+        .route(
+            "/customers/{customer_id}",
+            get(find_customer).put(update_customer),
+        )
+        .route("/customers/{customer_id}/rooms", get(find_customer_rooms))
+        .route(
+            "/admin/agents/{agent_id}/capacity",
+            put(update_agent_capacity),
+        )
+        .route("/admin/agents/{agent_id}/skills", put(update_agent_skills))
+        .route("/admin/agents/{agent_id}/queues", put(update_agent_queues))
+        .route(
+            "/admin/customers/{customer_id}/tier",
+            put(update_customer_tier),
+        )
+        .route("/queues", get(find_queues))
+        .route("/admin/queues", post(create_queue))
+        .route("/admin/queues/{name}", put(update_queue))
+        .route("/queues/{name}/availability", get(find_queue_availability))
+        .route("/queues/{name}/hours", get(find_business_hours))
+        .route("/admin/queues/{name}/hours", put(update_business_hours))
+        .route("/queues/{name}/sla", get(find_sla_targets))
+        .route("/admin/queues/{name}/sla", put(update_sla_targets))
+        .route("/holidays", get(find_holidays))
+        .route("/admin/holidays", post(create_holiday))
+        .route("/admin/holidays/{holiday_id}", delete(delete_holiday))
+        .route("/offline-messages", get(find_pending_messages))
+        .route(
+            "/offline-messages/{offline_message_id}/pick",
+            post(pick_message),
+        )
 }
@@ -0,0 +1,4 @@
+pub mod offline_handler;
+pub mod offline_message;
+pub mod offline_repository;
+pub mod offline_service;
@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+
+use crate::config::{app_state::ArcAppState, session::RequiredAgent, MangJooResult};
+
+use super::{offline_message::OfflineMessage, offline_service};
+
+#[derive(Debug, Serialize)]
+pub struct OfflineMessagesResponse {
+    offline_messages: Vec<OfflineMessage>,
+}
+
+#[tracing::instrument]
+pub async fn find_pending_messages(
+    State(app_state): State<ArcAppState>,
+    RequiredAgent(session): RequiredAgent,
+) -> MangJooResult<Json<OfflineMessagesResponse>> {
+    let offline_messages = offline_service::find_pending_messages(
+        session.user_id,
+        &app_state.agents,
+        &app_state.offline_messages,
+    )
+    .await?;
+
+    Ok(Json(OfflineMessagesResponse { offline_messages }))
+}
+
+#[tracing::instrument]
+pub async fn pick_message(
+    State(app_state): State<ArcAppState>,
+    RequiredAgent(session): RequiredAgent,
+    Path(offline_message_id): Path<i64>,
+) -> MangJooResult<Json<OfflineMessage>> {
+    offline_service::pick_message(
+        offline_message_id,
+        session.user_id,
+        &app_state.agents,
+        &app_state.offline_messages,
+    )
+    .await
+    .map(Json)
+}
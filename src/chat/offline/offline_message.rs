@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+// 영업 시간 밖에 고객이 남긴 문의 (상담원이 가져가기 전까지 picked_by 가 비어 있다)
+#[derive(Debug, Clone, Serialize)]
+pub struct OfflineMessage {
+    pub offline_message_id: i64,
+    pub customer_id: i64,
+    pub queue: String,
+    pub topic: Option<String>,
+    pub language: Option<String>,
+    pub body: String,
+    pub picked_by: Option<i64>,
+    pub picked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::config::{error::AppError, MangJooResult};
+
+use super::offline_message::OfflineMessage;
+
+#[derive(Debug, Clone)]
+pub struct OfflineMessageRepository {
+    pool: PgPool,
+}
+
+impl OfflineMessageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn save(
+        &self,
+        customer_id: i64,
+        queue: &str,
+        topic: Option<&str>,
+        language: Option<&str>,
+        body: &str,
+    ) -> MangJooResult<OfflineMessage> {
+        let entity = sqlx::query_as!(
+            OfflineMessageEntity,
+            "INSERT INTO offline_messages (customer_id, queue, topic, language, body)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING offline_message_id, customer_id, queue, topic, language, body,
+                picked_by, picked_at, created_at
+            ",
+            customer_id,
+            queue,
+            topic,
+            language,
+            body
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(OfflineMessage::from(entity))
+    }
+
+    // queues 가 None 이면 모든 대기열, 먼저 남긴 문의부터
+    pub async fn find_pending(
+        &self,
+        queues: Option<&[String]>,
+        limit: i64,
+    ) -> MangJooResult<Vec<OfflineMessage>> {
+        let entities = sqlx::query_as!(
+            OfflineMessageEntity,
+            "SELECT offline_message_id, customer_id, queue, topic, language, body,
+                picked_by, picked_at, created_at
+            FROM offline_messages
+            WHERE picked_by IS NULL
+              AND ($1::VARCHAR[] IS NULL OR queue = ANY($1))
+            ORDER BY offline_message_id
+            LIMIT $2
+            ",
+            queues,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(OfflineMessage::from).collect())
+    }
+
+    pub async fn count_pending(&self, queues: Option<&[String]>) -> MangJooResult<i64> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!"
+            FROM offline_messages
+            WHERE picked_by IS NULL
+              AND ($1::VARCHAR[] IS NULL OR queue = ANY($1))
+            "#,
+            queues
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))
+    }
+
+    // 아직 아무도 가져가지 않은 문의만 가져간다 (동시에 가져가려 하면 한 명만 성공)
+    pub async fn pick(
+        &self,
+        offline_message_id: i64,
+        agent_id: i64,
+        queues: Option<&[String]>,
+    ) -> MangJooResult<Option<OfflineMessage>> {
+        let entity = sqlx::query_as!(
+            OfflineMessageEntity,
+            "UPDATE offline_messages
+            SET picked_by = $2, picked_at = NOW()
+            WHERE offline_message_id = $1
+              AND picked_by IS NULL
+              AND ($3::VARCHAR[] IS NULL OR queue = ANY($3))
+            RETURNING offline_message_id, customer_id, queue, topic, language, body,
+                picked_by, picked_at, created_at
+            ",
+            offline_message_id,
+            agent_id,
+            queues
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(OfflineMessage::from))
+    }
+}
+
+#[derive(Debug)]
+pub struct OfflineMessageEntity {
+    offline_message_id: i64,
+    customer_id: i64,
+    queue: String,
+    topic: Option<String>,
+    language: Option<String>,
+    body: String,
+    picked_by: Option<i64>,
+    picked_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<OfflineMessageEntity> for OfflineMessage {
+    fn from(entity: OfflineMessageEntity) -> Self {
+        OfflineMessage {
+            offline_message_id: entity.offline_message_id,
+            customer_id: entity.customer_id,
+            queue: entity.queue,
+            topic: entity.topic,
+            language: entity.language,
+            body: entity.body,
+            picked_by: entity.picked_by,
+            picked_at: entity.picked_at,
+            created_at: entity.created_at,
+        }
+    }
+}
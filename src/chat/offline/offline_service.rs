@@ -0,0 +1,89 @@
+use crate::{
+    chat::agent::agent::Agents,
+    config::{error::AppError, MangJooResult},
+};
+
+use super::{offline_message::OfflineMessage, offline_repository::OfflineMessageRepository};
+
+const MAX_OFFLINE_MESSAGE_LENGTH: usize = 2000;
+const PENDING_MESSAGE_LIMIT: i64 = 100;
+
+// 영업 시간 밖에 방 대신 남기는 문의
+pub async fn leave_message(
+    customer_id: i64,
+    queue: &str,
+    topic: Option<&str>,
+    language: Option<&str>,
+    body: Option<&str>,
+    offline_messages: &OfflineMessageRepository,
+) -> MangJooResult<OfflineMessage> {
+    let body = body.map(str::trim).unwrap_or_default();
+    if body.is_empty() {
+        return Err(AppError::InvalidRequest(format!(
+            "Queue {} is closed, leave a message instead",
+            queue
+        )));
+    }
+    if body.chars().count() > MAX_OFFLINE_MESSAGE_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "Message must be at most {} characters",
+            MAX_OFFLINE_MESSAGE_LENGTH
+        )));
+    }
+
+    offline_messages
+        .save(customer_id, queue, topic, language, body)
+        .await
+}
+
+// 상담원이 구독한 대기열의 밀린 문의
+pub async fn find_pending_messages(
+    agent_id: i64,
+    agents: &Agents,
+    offline_messages: &OfflineMessageRepository,
+) -> MangJooResult<Vec<OfflineMessage>> {
+    let queues = find_agent_queues(agent_id, agents).await;
+
+    offline_messages
+        .find_pending(queues.as_deref(), PENDING_MESSAGE_LIMIT)
+        .await
+}
+
+pub async fn count_pending_messages(
+    agent_id: i64,
+    agents: &Agents,
+    offline_messages: &OfflineMessageRepository,
+) -> MangJooResult<i64> {
+    let queues = find_agent_queues(agent_id, agents).await;
+
+    offline_messages.count_pending(queues.as_deref()).await
+}
+
+pub async fn pick_message(
+    offline_message_id: i64,
+    agent_id: i64,
+    agents: &Agents,
+    offline_messages: &OfflineMessageRepository,
+) -> MangJooResult<OfflineMessage> {
+    let queues = find_agent_queues(agent_id, agents).await;
+
+    offline_messages
+        .pick(offline_message_id, agent_id, queues.as_deref())
+        .await?
+        .ok_or_else(|| {
+            AppError::InvalidRequest(format!(
+                "Offline message {} is already picked or not in your queues",
+                offline_message_id
+            ))
+        })
+}
+
+// 구독한 대기열이 없으면 모든 대기열을 맡는다
+async fn find_agent_queues(agent_id: i64, agents: &Agents) -> Option<Vec<String>> {
+    let queues = agents.find_queues(agent_id).await;
+    if queues.is_empty() {
+        return None;
+    }
+
+    Some(queues.into_iter().collect())
+}
@@ -0,0 +1,296 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Datelike, Days, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{error::AppError, MangJooResult};
+
+// 다음 영업 시작을 찾을 때 살펴보는 최대 일수 (휴일이 길게 이어져도 1년 안에는 연다)
+const NEXT_OPEN_SEARCH_DAYS: u64 = 366;
+// 서머타임 전환으로 건너뛰는 최대 시간 (분)
+const MAX_DST_GAP_MINUTES: i64 = 3 * 60;
+
+// 요일별 영업 시간 (대기열의 시간대 기준, close_time 은 포함하지 않는다)
+// close_time 이 open_time 보다 이르면 다음 날 close_time 까지 영업한다 (예: 금 22:00 ~ 토 02:00)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessHours {
+    pub weekday: Weekday,
+    pub open_time: NaiveTime,
+    pub close_time: NaiveTime,
+}
+
+impl BusinessHours {
+    pub fn is_overnight(&self) -> bool {
+        self.close_time < self.open_time
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Holiday {
+    pub holiday_id: i64,
+    // None 이면 모든 대기열의 휴일
+    pub queue: Option<String>,
+    pub holiday: NaiveDate,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// 대기열이 지금 상담을 받는지와 다음에 여는 시각
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueAvailability {
+    pub queue: String,
+    pub open: bool,
+    pub next_open_at: Option<DateTime<Utc>>,
+}
+
+// 대기열의 영업 시간과 휴일
+// 영업 시간이 없으면 휴일을 뺀 모든 시간에 영업한다
+// 휴일에는 하루 종일 닫는다 (전날 밤부터 이어지는 영업 시간도 자정에 닫는다)
+#[derive(Debug, Clone)]
+pub struct QueueSchedule {
+    time_zone: Tz,
+    hours: Vec<BusinessHours>,
+    holidays: HashSet<NaiveDate>,
+}
+
+impl QueueSchedule {
+    pub fn new(time_zone: Tz, mut hours: Vec<BusinessHours>, holidays: HashSet<NaiveDate>) -> Self {
+        hours.sort_by_key(|hours| (hours.weekday.num_days_from_monday(), hours.open_time));
+        Self {
+            time_zone,
+            hours,
+            holidays,
+        }
+    }
+
+    pub fn availability(&self, queue: &str, now: DateTime<Utc>) -> QueueAvailability {
+        let open = self.is_open(now);
+        QueueAvailability {
+            queue: queue.to_string(),
+            open,
+            next_open_at: if open { None } else { self.next_open_at(now) },
+        }
+    }
+
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&self.time_zone);
+        let today = local.date_naive();
+        if self.holidays.contains(&today) {
+            return false;
+        }
+        if self.hours.is_empty() {
+            return true;
+        }
+
+        let time = local.time();
+        let opened_today = self.hours_of(today.weekday()).any(|hours| {
+            hours.open_time <= time && (hours.is_overnight() || time < hours.close_time)
+        });
+        // 전날 밤에 열어 자정을 넘긴 영업 시간
+        let opened_yesterday = self
+            .hours_of(today.weekday().pred())
+            .any(|hours| hours.is_overnight() && time < hours.close_time);
+
+        opened_today || opened_yesterday
+    }
+
+    // 닫혀 있는 동안 다음 영업 시작 시각
+    pub fn next_open_at(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let today = now.with_timezone(&self.time_zone).date_naive();
+
+        (0..=NEXT_OPEN_SEARCH_DAYS)
+            .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+            .filter(|date| !self.holidays.contains(date))
+            .find_map(|date| {
+                self.open_times(date)
+                    .into_iter()
+                    .filter_map(|open_time| self.to_utc(date, open_time))
+                    .find(|open_at| *open_at > now)
+            })
+    }
+
+    // 그날 영업을 시작할 수 있는 시각 (순서대로)
+    // 전날 밤부터 이어지는 영업 시간은 전날이 휴일이면 자정에 연다
+    fn open_times(&self, date: NaiveDate) -> Vec<NaiveTime> {
+        if self.hours.is_empty() {
+            return vec![NaiveTime::MIN];
+        }
+
+        let continues_overnight = self
+            .hours_of(date.weekday().pred())
+            .any(|hours| hours.is_overnight() && hours.close_time > NaiveTime::MIN);
+        continues_overnight
+            .then_some(NaiveTime::MIN)
+            .into_iter()
+            .chain(self.hours_of(date.weekday()).map(|hours| hours.open_time))
+            .collect()
+    }
+
+    fn hours_of(&self, weekday: Weekday) -> impl Iterator<Item = &BusinessHours> {
+        self.hours
+            .iter()
+            .filter(move |hours| hours.weekday == weekday)
+    }
+
+    // 서머타임으로 건너뛴 시각이면 건너뛴 구간이 끝나는 시각에 연다
+    // 겹치는 시각이면 먼저 오는 시각에 연다
+    fn to_utc(&self, date: NaiveDate, time: NaiveTime) -> Option<DateTime<Utc>> {
+        let local = date.and_time(time);
+
+        (0..=MAX_DST_GAP_MINUTES)
+            .filter_map(|minutes| local.checked_add_signed(Duration::minutes(minutes)))
+            .find_map(|local| self.time_zone.from_local_datetime(&local).earliest())
+            .map(|local| local.with_timezone(&Utc))
+    }
+}
+
+// IANA 시간대 이름 (예: Asia/Seoul)
+pub fn parse_time_zone(time_zone: &str) -> MangJooResult<Tz> {
+    time_zone
+        .trim()
+        .parse::<Tz>()
+        .map_err(|_| AppError::InvalidRequest(format!("Unknown time zone {}", time_zone)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::{America::New_York, Asia::Seoul};
+
+    use super::*;
+
+    fn hours(weekday: Weekday, open_time: &str, close_time: &str) -> BusinessHours {
+        BusinessHours {
+            weekday,
+            open_time: open_time.parse().unwrap(),
+            close_time: close_time.parse().unwrap(),
+        }
+    }
+
+    fn weekdays(open_time: &str, close_time: &str) -> Vec<BusinessHours> {
+        [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ]
+        .into_iter()
+        .map(|weekday| hours(weekday, open_time, close_time))
+        .collect()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn closed_all_day_on_holiday() {
+        // 2025-05-05 (월) 어린이날
+        let schedule = QueueSchedule::new(
+            Seoul,
+            weekdays("09:00:00", "18:00:00"),
+            HashSet::from([date("2025-05-05")]),
+        );
+
+        // 월 10:00 KST
+        let now = utc("2025-05-05T01:00:00Z");
+        assert!(!schedule.is_open(now));
+        // 화 09:00 KST
+        assert_eq!(
+            schedule.next_open_at(now),
+            Some(utc("2025-05-06T00:00:00Z"))
+        );
+        assert!(schedule.is_open(utc("2025-05-06T00:00:00Z")));
+    }
+
+    #[test]
+    fn overnight_hours_stay_open_past_midnight() {
+        let schedule = QueueSchedule::new(
+            Seoul,
+            vec![hours(Weekday::Fri, "22:00:00", "02:00:00")],
+            HashSet::new(),
+        );
+
+        // 금 21:00 KST 에는 닫혀 있고 22:00 에 연다
+        assert!(!schedule.is_open(utc("2025-05-09T12:00:00Z")));
+        assert_eq!(
+            schedule.next_open_at(utc("2025-05-09T12:00:00Z")),
+            Some(utc("2025-05-09T13:00:00Z"))
+        );
+        // 금 23:00, 토 01:59 KST
+        assert!(schedule.is_open(utc("2025-05-09T14:00:00Z")));
+        assert!(schedule.is_open(utc("2025-05-09T16:59:00Z")));
+        // 토 02:00 KST 에 닫고 다음 주 금 22:00 에 연다
+        assert!(!schedule.is_open(utc("2025-05-09T17:00:00Z")));
+        assert_eq!(
+            schedule.next_open_at(utc("2025-05-09T17:00:00Z")),
+            Some(utc("2025-05-16T13:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn overnight_hours_after_holiday_open_at_midnight() {
+        // 금요일이 휴일이면 토요일 자정부터 남은 시간만 영업한다
+        let schedule = QueueSchedule::new(
+            Seoul,
+            vec![hours(Weekday::Fri, "22:00:00", "02:00:00")],
+            HashSet::from([date("2025-05-09")]),
+        );
+
+        // 금 23:00 KST
+        let now = utc("2025-05-09T14:00:00Z");
+        assert!(!schedule.is_open(now));
+        // 토 00:00 KST
+        assert_eq!(
+            schedule.next_open_at(now),
+            Some(utc("2025-05-09T15:00:00Z"))
+        );
+        assert!(schedule.is_open(utc("2025-05-09T15:30:00Z")));
+    }
+
+    #[test]
+    fn follows_daylight_saving_time() {
+        // 2025-03-09 (일) 02:00 EST 에 03:00 EDT 로 바뀐다
+        let schedule = QueueSchedule::new(
+            New_York,
+            vec![hours(Weekday::Sun, "09:00:00", "17:00:00")],
+            HashSet::new(),
+        );
+
+        // 일주일 전 09:00 EST 는 14:00 UTC, 당일 09:00 EDT 는 13:00 UTC
+        assert!(!schedule.is_open(utc("2025-03-02T13:00:00Z")));
+        assert!(schedule.is_open(utc("2025-03-02T14:00:00Z")));
+        assert_eq!(
+            schedule.next_open_at(utc("2025-03-09T05:00:00Z")),
+            Some(utc("2025-03-09T13:00:00Z"))
+        );
+        assert!(!schedule.is_open(utc("2025-03-09T12:59:00Z")));
+        assert!(schedule.is_open(utc("2025-03-09T13:00:00Z")));
+        assert!(!schedule.is_open(utc("2025-03-09T21:00:00Z")));
+    }
+
+    #[test]
+    fn opens_when_daylight_saving_gap_ends() {
+        // 02:30 은 2025-03-09 에는 없는 시각이므로 03:00 EDT 에 연다
+        let schedule = QueueSchedule::new(
+            New_York,
+            vec![hours(Weekday::Sun, "02:30:00", "04:00:00")],
+            HashSet::new(),
+        );
+
+        // 01:00 EST
+        let now = utc("2025-03-09T06:00:00Z");
+        assert!(!schedule.is_open(now));
+        assert_eq!(
+            schedule.next_open_at(now),
+            Some(utc("2025-03-09T07:00:00Z"))
+        );
+        // 03:30 EDT
+        assert!(schedule.is_open(utc("2025-03-09T07:30:00Z")));
+    }
+}
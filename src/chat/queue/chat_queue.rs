@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 대기열을 고르지 않은 방이 들어가는 기본 대기열 (마이그레이션에서 생성)
+pub const DEFAULT_QUEUE: &str = "default";
+
+// 상담 대기열 (우선순위가 높은 대기열의 방부터 배정한다)
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatQueue {
+    pub name: String,
+    pub priority: i32,
+    // 영업 시간과 휴일을 해석하는 IANA 시간대
+    pub time_zone: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 대기열별 SLA 목표 (초, None 이면 그 지표는 위반으로 보지 않는다)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlaTargets {
+    pub time_to_assign_secs: Option<i32>,
+    pub first_response_secs: Option<i32>,
+    pub handle_time_secs: Option<i32>,
+}
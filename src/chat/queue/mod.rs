@@ -0,0 +1,5 @@
+pub mod business_hours;
+pub mod chat_queue;
+pub mod queue_handler;
+pub mod queue_repository;
+pub mod queue_service;
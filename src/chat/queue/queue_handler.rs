@@ -0,0 +1,218 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{
+    app_state::ArcAppState,
+    session::{AuthUser, RequiredAdmin},
+    MangJooResult,
+};
+
+use super::{
+    business_hours::{BusinessHours, Holiday, QueueAvailability},
+    chat_queue::{ChatQueue, SlaTargets},
+    queue_service,
+};
+
+#[derive(Debug, Serialize)]
+pub struct QueuesResponse {
+    queues: Vec<ChatQueue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateQueueRequest {
+    name: String,
+    #[serde(default)]
+    priority: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateQueueRequest {
+    priority: i32,
+}
+
+// 고객이 방을 만들 때 고를 수 있는 대기열 목록
+#[tracing::instrument]
+pub async fn find_queues(
+    State(app_state): State<ArcAppState>,
+    AuthUser(_session): AuthUser,
+) -> MangJooResult<Json<QueuesResponse>> {
+    let queues = app_state.chat_queues.find_all().await?;
+
+    Ok(Json(QueuesResponse { queues }))
+}
+
+#[tracing::instrument]
+pub async fn create_queue(
+    State(app_state): State<ArcAppState>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Json(request): Json<CreateQueueRequest>,
+) -> MangJooResult<Json<ChatQueue>> {
+    queue_service::create_queue(&request.name, request.priority, &app_state.chat_queues)
+        .await
+        .map(Json)
+}
+
+#[tracing::instrument]
+pub async fn update_queue(
+    State(app_state): State<ArcAppState>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Path(name): Path<String>,
+    Json(request): Json<UpdateQueueRequest>,
+) -> MangJooResult<Json<ChatQueue>> {
+    let queue =
+        queue_service::update_queue(&name, request.priority, &app_state.chat_queues).await?;
+    app_state.dispatch_notify.notify_one();
+
+    Ok(Json(queue))
+}
+
+// 고객이 방을 만들기 전에 상담 가능한지 확인한다
+#[tracing::instrument]
+pub async fn find_queue_availability(
+    State(app_state): State<ArcAppState>,
+    AuthUser(_session): AuthUser,
+    Path(name): Path<String>,
+) -> MangJooResult<Json<QueueAvailability>> {
+    let queue = queue_service::find_queue(&name, &app_state.chat_queues).await?;
+
+    queue_service::find_availability(&queue, &app_state.chat_queues)
+        .await
+        .map(Json)
+}
+
+#[derive(Debug, Serialize)]
+pub struct BusinessHoursResponse {
+    queue: String,
+    time_zone: String,
+    hours: Vec<BusinessHours>,
+}
+
+impl BusinessHoursResponse {
+    fn new(queue: ChatQueue, hours: Vec<BusinessHours>) -> Self {
+        Self {
+            queue: queue.name,
+            time_zone: queue.time_zone,
+            hours,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateBusinessHoursRequest {
+    time_zone: String,
+    hours: Vec<BusinessHours>,
+}
+
+#[tracing::instrument]
+pub async fn find_business_hours(
+    State(app_state): State<ArcAppState>,
+    AuthUser(_session): AuthUser,
+    Path(name): Path<String>,
+) -> MangJooResult<Json<BusinessHoursResponse>> {
+    let (queue, hours) = queue_service::find_business_hours(&name, &app_state.chat_queues).await?;
+
+    Ok(Json(BusinessHoursResponse::new(queue, hours)))
+}
+
+#[tracing::instrument]
+pub async fn update_business_hours(
+    State(app_state): State<ArcAppState>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Path(name): Path<String>,
+    Json(request): Json<UpdateBusinessHoursRequest>,
+) -> MangJooResult<Json<BusinessHoursResponse>> {
+    let (queue, hours) = queue_service::update_business_hours(
+        &name,
+        &request.time_zone,
+        request.hours,
+        &app_state.chat_queues,
+    )
+    .await?;
+
+    Ok(Json(BusinessHoursResponse::new(queue, hours)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HolidaysQuery {
+    queue: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HolidaysResponse {
+    holidays: Vec<Holiday>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateHolidayRequest {
+    // 없으면 모든 대기열의 휴일
+    queue: Option<String>,
+    holiday: NaiveDate,
+    name: String,
+}
+
+#[tracing::instrument]
+pub async fn find_holidays(
+    State(app_state): State<ArcAppState>,
+    AuthUser(_session): AuthUser,
+    Query(query): Query<HolidaysQuery>,
+) -> MangJooResult<Json<HolidaysResponse>> {
+    let holidays =
+        queue_service::find_holidays(query.queue.as_deref(), &app_state.chat_queues).await?;
+
+    Ok(Json(HolidaysResponse { holidays }))
+}
+
+#[tracing::instrument]
+pub async fn create_holiday(
+    State(app_state): State<ArcAppState>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Json(request): Json<CreateHolidayRequest>,
+) -> MangJooResult<Json<Holiday>> {
+    queue_service::create_holiday(
+        request.queue.as_deref(),
+        request.holiday,
+        &request.name,
+        &app_state.chat_queues,
+    )
+    .await
+    .map(Json)
+}
+
+#[tracing::instrument]
+pub async fn delete_holiday(
+    State(app_state): State<ArcAppState>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Path(holiday_id): Path<i64>,
+) -> MangJooResult<StatusCode> {
+    queue_service::delete_holiday(holiday_id, &app_state.chat_queues).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[tracing::instrument]
+pub async fn find_sla_targets(
+    State(app_state): State<ArcAppState>,
+    AuthUser(_session): AuthUser,
+    Path(name): Path<String>,
+) -> MangJooResult<Json<SlaTargets>> {
+    queue_service::find_sla_targets(&name, &app_state.chat_queues)
+        .await
+        .map(Json)
+}
+
+#[tracing::instrument]
+pub async fn update_sla_targets(
+    State(app_state): State<ArcAppState>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Path(name): Path<String>,
+    Json(targets): Json<SlaTargets>,
+) -> MangJooResult<Json<SlaTargets>> {
+    queue_service::update_sla_targets(&name, targets, &app_state.chat_queues)
+        .await
+        .map(Json)
+}
@@ -0,0 +1,332 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc, Weekday};
+use sqlx::PgPool;
+
+use crate::config::{error::AppError, MangJooResult};
+
+use super::{
+    business_hours::{BusinessHours, Holiday},
+    chat_queue::{ChatQueue, SlaTargets},
+};
+
+#[derive(Debug, Clone)]
+pub struct ChatQueueRepository {
+    pool: PgPool,
+}
+
+impl ChatQueueRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // 우선순위가 높은 대기열부터
+    pub async fn find_all(&self) -> MangJooResult<Vec<ChatQueue>> {
+        let entities = sqlx::query_as!(
+            ChatQueueEntity,
+            "SELECT name, priority, time_zone, created_at, updated_at
+            FROM chat_queues
+            ORDER BY priority DESC, name
+            "
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(ChatQueue::from).collect())
+    }
+
+    pub async fn find_by_name(&self, name: &str) -> MangJooResult<Option<ChatQueue>> {
+        let entity = sqlx::query_as!(
+            ChatQueueEntity,
+            "SELECT name, priority, time_zone, created_at, updated_at
+            FROM chat_queues
+            WHERE name = $1
+            ",
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(ChatQueue::from))
+    }
+
+    // 이미 있는 이름이면 None
+    pub async fn save(&self, name: &str, priority: i32) -> MangJooResult<Option<ChatQueue>> {
+        let entity = sqlx::query_as!(
+            ChatQueueEntity,
+            "INSERT INTO chat_queues (name, priority)
+            VALUES ($1, $2)
+            ON CONFLICT (name) DO NOTHING
+            RETURNING name, priority, time_zone, created_at, updated_at
+            ",
+            name,
+            priority
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(ChatQueue::from))
+    }
+
+    pub async fn update_priority(
+        &self,
+        name: &str,
+        priority: i32,
+    ) -> MangJooResult<Option<ChatQueue>> {
+        let entity = sqlx::query_as!(
+            ChatQueueEntity,
+            "UPDATE chat_queues
+            SET priority = $2, updated_at = NOW()
+            WHERE name = $1
+            RETURNING name, priority, time_zone, created_at, updated_at
+            ",
+            name,
+            priority
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(ChatQueue::from))
+    }
+
+    pub async fn find_business_hours(&self, queue: &str) -> MangJooResult<Vec<BusinessHours>> {
+        let entities = sqlx::query_as!(
+            BusinessHoursEntity,
+            "SELECT weekday, open_time, close_time
+            FROM queue_business_hours
+            WHERE queue = $1
+            ORDER BY weekday, open_time
+            ",
+            queue
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        entities.into_iter().map(BusinessHours::try_from).collect()
+    }
+
+    // 시간대와 영업 시간을 함께 바꾼다 (없는 대기열이면 None)
+    pub async fn replace_business_hours(
+        &self,
+        queue: &str,
+        time_zone: &str,
+        hours: &[BusinessHours],
+    ) -> MangJooResult<Option<ChatQueue>> {
+        let weekdays: Vec<i16> = hours
+            .iter()
+            .map(|hours| hours.weekday.num_days_from_monday() as i16)
+            .collect();
+        let open_times: Vec<NaiveTime> = hours.iter().map(|hours| hours.open_time).collect();
+        let close_times: Vec<NaiveTime> = hours.iter().map(|hours| hours.close_time).collect();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        let entity = sqlx::query_as!(
+            ChatQueueEntity,
+            "UPDATE chat_queues
+            SET time_zone = $2, updated_at = NOW()
+            WHERE name = $1
+            RETURNING name, priority, time_zone, created_at, updated_at
+            ",
+            queue,
+            time_zone
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        let Some(entity) = entity else {
+            return Ok(None);
+        };
+
+        sqlx::query!("DELETE FROM queue_business_hours WHERE queue = $1", queue)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        sqlx::query!(
+            "INSERT INTO queue_business_hours (queue, weekday, open_time, close_time)
+            SELECT $1, weekday, open_time, close_time
+            FROM UNNEST($2::SMALLINT[], $3::TIME[], $4::TIME[]) AS hours (weekday, open_time, close_time)
+            ",
+            queue,
+            &weekdays,
+            &open_times,
+            &close_times
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+        tx.commit()
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(Some(ChatQueue::from(entity)))
+    }
+
+    // queue 를 주면 그 대기열에 적용되는 휴일 (모든 대기열 휴일 포함)
+    pub async fn find_holidays(
+        &self,
+        queue: Option<&str>,
+        from: NaiveDate,
+    ) -> MangJooResult<Vec<Holiday>> {
+        let entities = sqlx::query_as!(
+            HolidayEntity,
+            "SELECT holiday_id, queue, holiday, name, created_at
+            FROM holidays
+            WHERE ($1::VARCHAR IS NULL OR queue IS NULL OR queue = $1)
+              AND holiday >= $2
+            ORDER BY holiday, holiday_id
+            ",
+            queue,
+            from
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entities.into_iter().map(Holiday::from).collect())
+    }
+
+    // 같은 날 같은 대기열의 휴일이 이미 있으면 None
+    pub async fn save_holiday(
+        &self,
+        queue: Option<&str>,
+        holiday: NaiveDate,
+        name: &str,
+    ) -> MangJooResult<Option<Holiday>> {
+        let entity = sqlx::query_as!(
+            HolidayEntity,
+            "INSERT INTO holidays (queue, holiday, name)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            RETURNING holiday_id, queue, holiday, name, created_at
+            ",
+            queue,
+            holiday,
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(entity.map(Holiday::from))
+    }
+
+    pub async fn find_sla_targets(&self, queue: &str) -> MangJooResult<Option<SlaTargets>> {
+        sqlx::query_as!(
+            SlaTargets,
+            "SELECT time_to_assign_secs, first_response_secs, handle_time_secs
+            FROM queue_sla_targets
+            WHERE queue = $1
+            ",
+            queue
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))
+    }
+
+    pub async fn save_sla_targets(&self, queue: &str, targets: &SlaTargets) -> MangJooResult<()> {
+        sqlx::query!(
+            "INSERT INTO queue_sla_targets (queue, time_to_assign_secs, first_response_secs, handle_time_secs)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (queue) DO UPDATE
+            SET time_to_assign_secs = EXCLUDED.time_to_assign_secs,
+                first_response_secs = EXCLUDED.first_response_secs,
+                handle_time_secs = EXCLUDED.handle_time_secs,
+                updated_at = NOW()
+            ",
+            queue,
+            targets.time_to_assign_secs,
+            targets.first_response_secs,
+            targets.handle_time_secs
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(())
+    }
+
+    pub async fn delete_holiday(&self, holiday_id: i64) -> MangJooResult<bool> {
+        let result = sqlx::query!("DELETE FROM holidays WHERE holiday_id = $1", holiday_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[derive(Debug)]
+pub struct ChatQueueEntity {
+    name: String,
+    priority: i32,
+    time_zone: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ChatQueueEntity> for ChatQueue {
+    fn from(entity: ChatQueueEntity) -> Self {
+        ChatQueue {
+            name: entity.name,
+            priority: entity.priority,
+            time_zone: entity.time_zone,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BusinessHoursEntity {
+    weekday: i16,
+    open_time: NaiveTime,
+    close_time: NaiveTime,
+}
+
+impl TryFrom<BusinessHoursEntity> for BusinessHours {
+    type Error = AppError;
+
+    fn try_from(entity: BusinessHoursEntity) -> Result<Self, Self::Error> {
+        let weekday = u8::try_from(entity.weekday)
+            .ok()
+            .and_then(|weekday| Weekday::try_from(weekday).ok())
+            .ok_or_else(|| {
+                AppError::InternalError(format!("Invalid weekday {}", entity.weekday))
+            })?;
+
+        Ok(BusinessHours {
+            weekday,
+            open_time: entity.open_time,
+            close_time: entity.close_time,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct HolidayEntity {
+    holiday_id: i64,
+    queue: Option<String>,
+    holiday: NaiveDate,
+    name: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<HolidayEntity> for Holiday {
+    fn from(entity: HolidayEntity) -> Self {
+        Holiday {
+            holiday_id: entity.holiday_id,
+            queue: entity.queue,
+            holiday: entity.holiday,
+            name: entity.name,
+            created_at: entity.created_at,
+        }
+    }
+}
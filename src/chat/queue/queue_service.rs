@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+
+use chrono::{Days, NaiveDate, Utc};
+
+use crate::config::{error::AppError, MangJooResult};
+
+use super::{
+    business_hours::{parse_time_zone, BusinessHours, Holiday, QueueAvailability, QueueSchedule},
+    chat_queue::{ChatQueue, SlaTargets},
+    queue_repository::ChatQueueRepository,
+};
+
+const MAX_QUEUE_NAME_LENGTH: usize = 50;
+const MAX_QUEUE_PRIORITY: i32 = 100;
+// 하루에 점심시간 등으로 나눠 여는 경우까지 감안한 요일별 최대 구간 수
+const MAX_BUSINESS_HOURS: usize = 7 * 4;
+const MAX_HOLIDAY_NAME_LENGTH: usize = 100;
+// 처리 시간까지 감안해 하루를 넘는 SLA 목표는 받지 않는다
+const MAX_SLA_TARGET_SECS: i32 = 24 * 60 * 60;
+
+pub async fn create_queue(
+    name: &str,
+    priority: i32,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<ChatQueue> {
+    let name = normalize_queue_name(name)?;
+    check_priority(priority)?;
+
+    chat_queues
+        .save(&name, priority)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest(format!("Queue {} already exists", name)))
+}
+
+pub async fn update_queue(
+    name: &str,
+    priority: i32,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<ChatQueue> {
+    let name = normalize_queue_name(name)?;
+    check_priority(priority)?;
+
+    chat_queues
+        .update_priority(&name, priority)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest(format!("Unknown queue {}", name)))
+}
+
+// 방 생성이나 상담원 구독 시 있는 대기열인지 확인한다
+pub async fn find_queue(name: &str, chat_queues: &ChatQueueRepository) -> MangJooResult<ChatQueue> {
+    let name = normalize_queue_name(name)?;
+
+    chat_queues
+        .find_by_name(&name)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest(format!("Unknown queue {}", name)))
+}
+
+// 지금 상담을 받는지 (영업 시간과 휴일은 대기열의 시간대 기준)
+pub async fn find_availability(
+    queue: &ChatQueue,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<QueueAvailability> {
+    let now = Utc::now();
+    let time_zone = parse_time_zone(&queue.time_zone)?;
+    let hours = chat_queues.find_business_hours(&queue.name).await?;
+    // 시간대에 따라 UTC 날짜보다 하루 이를 수 있다
+    let from = now
+        .date_naive()
+        .checked_sub_days(Days::new(1))
+        .unwrap_or(NaiveDate::MIN);
+    let holidays: HashSet<NaiveDate> = chat_queues
+        .find_holidays(Some(&queue.name), from)
+        .await?
+        .into_iter()
+        .map(|holiday| holiday.holiday)
+        .collect();
+
+    Ok(QueueSchedule::new(time_zone, hours, holidays).availability(&queue.name, now))
+}
+
+pub async fn find_business_hours(
+    name: &str,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<(ChatQueue, Vec<BusinessHours>)> {
+    let queue = find_queue(name, chat_queues).await?;
+    let hours = chat_queues.find_business_hours(&queue.name).await?;
+
+    Ok((queue, hours))
+}
+
+// 영업 시간을 비우면 항상 영업한다 (close_time 이 open_time 보다 이르면 자정을 넘겨 영업한다)
+pub async fn update_business_hours(
+    name: &str,
+    time_zone: &str,
+    hours: Vec<BusinessHours>,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<(ChatQueue, Vec<BusinessHours>)> {
+    let name = normalize_queue_name(name)?;
+    let time_zone = parse_time_zone(time_zone)?;
+    if hours.len() > MAX_BUSINESS_HOURS {
+        return Err(AppError::InvalidRequest(format!(
+            "Up to {} business hours are allowed",
+            MAX_BUSINESS_HOURS
+        )));
+    }
+    if let Some(invalid) = hours
+        .iter()
+        .find(|hours| hours.open_time == hours.close_time)
+    {
+        return Err(AppError::InvalidRequest(format!(
+            "open_time and close_time must differ on {}",
+            invalid.weekday
+        )));
+    }
+
+    let queue = chat_queues
+        .replace_business_hours(&name, time_zone.name(), &hours)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest(format!("Unknown queue {}", name)))?;
+    let hours = chat_queues.find_business_hours(&queue.name).await?;
+
+    Ok((queue, hours))
+}
+
+// 오늘 이후의 휴일
+pub async fn find_holidays(
+    queue: Option<&str>,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<Vec<Holiday>> {
+    chat_queues
+        .find_holidays(queue, Utc::now().date_naive())
+        .await
+}
+
+pub async fn create_holiday(
+    queue: Option<&str>,
+    holiday: NaiveDate,
+    name: &str,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<Holiday> {
+    let queue = match queue {
+        Some(queue) => Some(find_queue(queue, chat_queues).await?.name),
+        None => None,
+    };
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_HOLIDAY_NAME_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "Holiday name must be 1 to {} characters",
+            MAX_HOLIDAY_NAME_LENGTH
+        )));
+    }
+
+    chat_queues
+        .save_holiday(queue.as_deref(), holiday, name)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest(format!("Holiday on {} already exists", holiday)))
+}
+
+pub async fn delete_holiday(
+    holiday_id: i64,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<()> {
+    if !chat_queues.delete_holiday(holiday_id).await? {
+        return Err(AppError::InvalidRequest(format!(
+            "Unknown holiday {}",
+            holiday_id
+        )));
+    }
+
+    Ok(())
+}
+
+// 목표를 정하지 않은 대기열은 모든 지표가 비어 있다
+pub async fn find_sla_targets(
+    name: &str,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<SlaTargets> {
+    let queue = find_queue(name, chat_queues).await?;
+
+    Ok(chat_queues
+        .find_sla_targets(&queue.name)
+        .await?
+        .unwrap_or_default())
+}
+
+pub async fn update_sla_targets(
+    name: &str,
+    targets: SlaTargets,
+    chat_queues: &ChatQueueRepository,
+) -> MangJooResult<SlaTargets> {
+    let queue = find_queue(name, chat_queues).await?;
+    let is_valid = [
+        targets.time_to_assign_secs,
+        targets.first_response_secs,
+        targets.handle_time_secs,
+    ]
+    .into_iter()
+    .flatten()
+    .all(|secs| (1..=MAX_SLA_TARGET_SECS).contains(&secs));
+    if !is_valid {
+        return Err(AppError::InvalidRequest(format!(
+            "SLA targets must be between 1 and {} seconds",
+            MAX_SLA_TARGET_SECS
+        )));
+    }
+
+    chat_queues.save_sla_targets(&queue.name, &targets).await?;
+
+    Ok(targets)
+}
+
+// 대기열 이름은 소문자, 숫자, '-', '_' 만 쓴다 (예: billing, tech, vip)
+fn normalize_queue_name(name: &str) -> MangJooResult<String> {
+    let name = name.trim().to_lowercase();
+    let is_valid = !name.is_empty()
+        && name.chars().count() <= MAX_QUEUE_NAME_LENGTH
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    if !is_valid {
+        return Err(AppError::InvalidRequest(format!(
+            "Queue name must be 1 to {} characters of a-z, 0-9, '-' or '_'",
+            MAX_QUEUE_NAME_LENGTH
+        )));
+    }
+
+    Ok(name)
+}
+
+fn check_priority(priority: i32) -> MangJooResult<()> {
+    if !(0..=MAX_QUEUE_PRIORITY).contains(&priority) {
+        return Err(AppError::InvalidRequest(format!(
+            "priority must be between 0 and {}",
+            MAX_QUEUE_PRIORITY
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::config::app_state::ArcAppState;
+
+use super::chatting::{chat_event::ChatEvent, chat_service};
+
+const QUEUE_NOTIFY_INTERVAL: Duration = Duration::from_secs(15);
+
+// 대기 중인 고객에게 대기 순번과 예상 대기 시간을 주기적으로 알린다
+pub fn start_queue_notifier(state: ArcAppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(QUEUE_NOTIFY_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let queues = match state.chat_queues.find_all().await {
+                Ok(queues) => queues,
+                Err(err) => {
+                    tracing::error!("Can't load chat queues {:?}", err);
+                    continue;
+                }
+            };
+
+            for queue in queues {
+                let queue_statuses = match chat_service::find_queue_statuses(
+                    &queue.name,
+                    &state.rooms,
+                    &state.agents,
+                )
+                .await
+                {
+                    Ok(queue_statuses) => queue_statuses,
+                    Err(err) => {
+                        tracing::error!("Can't load queue statuses {:?}", err);
+                        continue;
+                    }
+                };
+
+                // 인스턴스마다 계산하므로 이 인스턴스에 연결된 소켓에만 보낸다
+                let socket_rooms = state.socket_rooms.read().await;
+                for queue_status in queue_statuses.iter() {
+                    if let Some(tx) = socket_rooms.get(&queue_status.room_id) {
+                        let _ = tx.send(ChatEvent::queue_position(queue_status));
+                    }
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,147 @@
+use super::{
+    agent::{
+        agent::Agent,
+        routing_strategy::{RoutingContext, RoutingStrategies, RoutingStrategy},
+    },
+    chatting::chat_room::ChatRoom,
+};
+
+// 대기 방에 맞는 상담원을 고른다
+// 필요한 스킬을 모두 가진 상담원 중 숙련도 합이 가장 높은 상담원들을 추리고,
+// 맞는 상담원이 없으면 일정 시간 기다린 뒤부터는 스킬과 관계없이 추린다
+// 추린 상담원 중 누구에게 배정할지는 대기열의 배정 전략이 정한다
+#[derive(Debug, Clone)]
+pub struct SkillRouter {
+    fallback_after_secs: i64,
+    strategies: RoutingStrategies,
+}
+
+impl SkillRouter {
+    pub fn new(fallback_after_secs: i64, strategies: RoutingStrategies) -> Self {
+        Self {
+            fallback_after_secs,
+            strategies,
+        }
+    }
+
+    pub fn strategy(&self, queue: &str) -> &dyn RoutingStrategy {
+        self.strategies.for_queue(queue)
+    }
+
+    // agents 는 새 상담을 받을 수 있는 상담원만 넘긴다
+    pub fn select_agent(
+        &self,
+        queue: &str,
+        room: &ChatRoom,
+        agents: &[(i64, Agent)],
+        context: &RoutingContext,
+    ) -> Option<i64> {
+        let required_skills = room.required_skills();
+        let scored: Vec<(i64, &Agent, u32)> = agents
+            .iter()
+            .filter_map(|(agent_id, agent)| {
+                let score = required_skills
+                    .iter()
+                    .map(|skill| agent.proficiency(skill).map(u32::from))
+                    .sum::<Option<u32>>()?;
+                Some((*agent_id, agent, score))
+            })
+            .collect();
+
+        let candidates: Vec<(i64, &Agent)> = match scored.iter().map(|(_, _, score)| *score).max() {
+            Some(best_score) => scored
+                .into_iter()
+                .filter(|(_, _, score)| *score == best_score)
+                .map(|(agent_id, agent, _)| (agent_id, agent))
+                .collect(),
+            None if room.waited_seconds() >= self.fallback_after_secs => agents
+                .iter()
+                .map(|(agent_id, agent)| (*agent_id, agent))
+                .collect(),
+            None => return None,
+        };
+
+        self.strategy(queue).select(&candidates, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use chrono::{Duration, Utc};
+
+    use crate::chat::agent::agent::AgentStatus;
+
+    use super::*;
+
+    const FALLBACK_AFTER_SECS: i64 = 60;
+
+    fn router() -> SkillRouter {
+        let strategies = RoutingStrategies::from_config("least_busy", "").unwrap();
+        SkillRouter::new(FALLBACK_AFTER_SECS, strategies)
+    }
+
+    fn agent(agent_id: i64, skills: &[(&str, u8)]) -> (i64, Agent) {
+        let mut agent = Agent::new(
+            agent_id.to_string(),
+            format!("agent {}", agent_id),
+            AgentStatus::Available,
+            HashSet::new(),
+            3,
+            Utc::now(),
+        );
+        agent.skills = skills
+            .iter()
+            .map(|(skill, proficiency)| (skill.to_string(), *proficiency))
+            .collect::<HashMap<_, _>>();
+        (agent_id, agent)
+    }
+
+    fn waiting_room(topic: &str, language: &str, waited_secs: i64) -> ChatRoom {
+        let created_at = Utc::now() - Duration::seconds(waited_secs);
+        serde_json::from_value(serde_json::json!({
+            "room_id": "room",
+            "customer_id": 1,
+            "topic": topic,
+            "language": language,
+            "agent_id": null,
+            "status": "Waiting",
+            "created_at": created_at,
+            "updated_at": created_at,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn agent_with_all_skills_and_best_proficiency_is_selected() {
+        let agents = vec![
+            agent(1, &[("billing", 5)]),
+            agent(2, &[("billing", 2), ("ko", 3)]),
+            agent(3, &[("billing", 4), ("ko", 4)]),
+        ];
+        let room = waiting_room("billing", "ko", 0);
+
+        let selected = router().select_agent("general", &room, &agents, &RoutingContext::default());
+
+        assert_eq!(selected, Some(3));
+    }
+
+    #[test]
+    fn no_matching_agent_waits_until_fallback() {
+        let agents = vec![agent(1, &[("billing", 5)])];
+
+        let fresh = waiting_room("refund", "en", 0);
+        assert_eq!(
+            router().select_agent("general", &fresh, &agents, &RoutingContext::default()),
+            None
+        );
+
+        let waited = waiting_room("refund", "en", FALLBACK_AFTER_SECS);
+        assert!(waited.waited_seconds() >= FALLBACK_AFTER_SECS);
+        assert_eq!(
+            router().select_agent("general", &waited, &agents, &RoutingContext::default()),
+            Some(1)
+        );
+    }
+}
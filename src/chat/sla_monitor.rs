@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::config::app_state::ArcAppState;
+
+use super::{
+    agent::agent::AgentNotification,
+    chatting::chat_sla::{SlaBreach, SlaMetric},
+};
+
+const SLA_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+// 이보다 오래된 방은 더 이상 살펴보지 않는다
+const SLA_LOOKBACK_SECS: i32 = 2 * 24 * 60 * 60;
+
+// 목표를 넘긴 방을 주기적으로 기록하고 경고한다
+// 위반은 방과 지표마다 한 번만 기록되므로 여러 인스턴스가 돌아도 경고는 한 번만 나간다
+pub fn start_sla_monitor(state: ArcAppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SLA_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let breaches = match state.chat_sla.record_breaches(SLA_LOOKBACK_SECS).await {
+                Ok(breaches) => breaches,
+                Err(err) => {
+                    tracing::error!("Can't check SLA breaches {:?}", err);
+                    continue;
+                }
+            };
+
+            for breach in breaches {
+                tracing::warn!(
+                    "SLA breached: room {:?} queue {} {} over {}s",
+                    breach.room_id,
+                    breach.queue,
+                    breach.metric,
+                    breach.target_secs
+                );
+                notify_breach(&state, &breach).await;
+            }
+        }
+    })
+}
+
+async fn notify_breach(state: &ArcAppState, breach: &SlaBreach) {
+    let agent_ids = match (breach.metric, breach.agent_id) {
+        (_, Some(agent_id)) => vec![agent_id],
+        (SlaMetric::TimeToAssign, None) => {
+            match state.agents.find_queue_agents(&breach.queue).await {
+                Ok(agent_ids) => agent_ids,
+                Err(err) => {
+                    tracing::error!("Can't load queue agents {:?}", err);
+                    return;
+                }
+            }
+        }
+        _ => return,
+    };
+
+    for agent_id in agent_ids {
+        let notification = AgentNotification::SlaBreached {
+            room_id: breach.room_id.clone(),
+            queue: breach.queue.clone(),
+            metric: breach.metric,
+            target_secs: breach.target_secs,
+            breached_at: breach.breached_at,
+        };
+        state.notify_agent(agent_id, notification).await;
+    }
+}
@@ -1,18 +1,38 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use async_redis_session::RedisSessionStore;
 use axum::extract::ws::{Message, WebSocket};
 use sqlx::PgPool;
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, Notify, RwLock};
 
 use crate::chat::{
-    agent::agent::Agents,
-    chatting::{chat_room::ChatRooms, ChatRoomId},
+    agent::{
+        agent::{AgentNotification, Agents},
+        agent_repository::AgentRepository,
+    },
+    chatting::{
+        attachment_storage::AttachmentStorage,
+        chat_escalation::{SupervisorNotification, SupervisorNotifier},
+        chat_event::ChatEvent,
+        chat_repository::{
+            AttachmentRepository, ChatMessageRepository, ChatRoomRepository, ChatSlaRepository,
+            ChatSurveyRepository, ChatTransferRepository,
+        },
+        chat_room::{ChatRooms, RoomStatus, RECONNECT_GRACE_SECS},
+        chat_service,
+        room_bus::RoomBus,
+        ChatRoomId,
+    },
+    customer::customer_repository::CustomerRepository,
+    offline::offline_repository::OfflineMessageRepository,
+    queue::queue_repository::ChatQueueRepository,
+    routing::SkillRouter,
 };
 
-use super::session::SessionManager;
+use super::{db::StateStores, session::SessionManager, MangJooResult};
 
 pub type ArcAppState = Arc<AppState>;
+pub type SocketRooms = Arc<RwLock<HashMap<ChatRoomId, broadcast::Sender<ChatEvent>>>>;
 
 #[derive(Debug, Clone)]
 pub struct AppState {
@@ -22,24 +42,152 @@ pub struct AppState {
     // 상담원 관리
     pub agents: Agents,
 
-    // 대기열 관리 (상담원 배정 대기 중인 방들)
-    pub waiting_queue: Arc<RwLock<Vec<ChatRoomId>>>,
-    pub socket_rooms: Arc<RwLock<HashMap<ChatRoomId, broadcast::Sender<Message>>>>,
+    // 이관 알림을 받는 팀장 소켓
+    pub supervisors: SupervisorNotifier,
+
+    // 대기열에 방이 들어오면 배정 작업을 깨운다
+    pub dispatch_notify: Arc<Notify>,
+    pub router: SkillRouter,
+    pub socket_rooms: SocketRooms,
+    // 인스턴스 간 방 이벤트 전달
+    pub room_bus: RoomBus,
+    pub chat_messages: ChatMessageRepository,
+    pub customers: CustomerRepository,
+    pub attachments: AttachmentRepository,
+    pub chat_surveys: ChatSurveyRepository,
+    pub chat_transfers: ChatTransferRepository,
+    pub chat_sla: ChatSlaRepository,
+    pub chat_queues: ChatQueueRepository,
+    pub offline_messages: OfflineMessageRepository,
+    pub attachment_storage: Arc<dyn AttachmentStorage>,
     pub db_pool: PgPool,
     pub session_store: SessionManager,
 }
 
 impl AppState {
-    pub fn new(db_pool: PgPool, redis_session_store: RedisSessionStore) -> Self {
+    pub fn new(
+        db_pool: PgPool,
+        redis_session_store: RedisSessionStore,
+        default_max_concurrent_chats: usize,
+        room_bus: RoomBus,
+        state_stores: StateStores,
+        attachment_storage: Arc<dyn AttachmentStorage>,
+        router: SkillRouter,
+    ) -> Self {
         Self {
-            rooms: ChatRooms::new(),
-            agents: Agents::new(),
-            waiting_queue: Arc::new(RwLock::new(Vec::new())),
+            rooms: ChatRooms::new(state_stores.rooms, ChatRoomRepository::new(db_pool.clone())),
+            agents: Agents::new(
+                state_stores.agents,
+                AgentRepository::new(db_pool.clone()),
+                default_max_concurrent_chats,
+            ),
+            supervisors: SupervisorNotifier::new(),
+            dispatch_notify: Arc::new(Notify::new()),
+            router,
             socket_rooms: Arc::new(RwLock::new(HashMap::new())),
+            room_bus,
+            chat_messages: ChatMessageRepository::new(db_pool.clone()),
+            customers: CustomerRepository::new(db_pool.clone()),
+            attachments: AttachmentRepository::new(db_pool.clone()),
+            chat_surveys: ChatSurveyRepository::new(db_pool.clone()),
+            chat_transfers: ChatTransferRepository::new(db_pool.clone()),
+            chat_sla: ChatSlaRepository::new(db_pool.clone()),
+            chat_queues: ChatQueueRepository::new(db_pool.clone()),
+            offline_messages: OfflineMessageRepository::new(db_pool.clone()),
+            attachment_storage,
             db_pool,
             session_store: SessionManager::new(redis_session_store),
         }
     }
+
+    // DB에 남아있는 방을 복구하고 소켓 채널을 다시 열어준다
+    pub async fn restore_rooms(self: &Arc<Self>) -> MangJooResult<()> {
+        let rooms = self.rooms.restore().await?;
+
+        {
+            let mut socket_rooms = self.socket_rooms.write().await;
+            for restored in rooms.iter() {
+                socket_rooms
+                    .entry(restored.room.room_id.clone())
+                    .or_insert_with(|| broadcast::channel(100).0);
+            }
+        }
+
+        // 새로 올린 대기 중인 방은 생성 순서대로 다시 대기열에 넣는다 (이미 있던 방은 대기열에도 남아 있다)
+        // 아무도 다시 들어오지 않는 방은 유예 시간 뒤 닫아 상담원의 자리를 돌려준다
+        for restored in rooms {
+            if restored.inserted && restored.room.status == RoomStatus::Waiting {
+                self.enqueue_room(restored.room.room_id.clone()).await;
+            }
+            self.schedule_abandon_check(restored.room.room_id);
+        }
+
+        Ok(())
+    }
+
+    // 유예 시간 뒤에도 모든 연결이 끊겨 있으면 방을 종료한다
+    pub fn schedule_abandon_check(self: &Arc<Self>, room_id: ChatRoomId) {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(RECONNECT_GRACE_SECS as u64)).await;
+            let grace_period = chrono::Duration::seconds(RECONNECT_GRACE_SECS);
+            match state.rooms.end_if_abandoned(&room_id, grace_period).await {
+                Ok(true) => state.close_room(&room_id).await,
+                Ok(false) => {}
+                Err(err) => tracing::error!("Can't check abandoned room {:?}", err),
+            }
+        });
+    }
+
+    pub async fn close_room(&self, room_id: &ChatRoomId) {
+        match chat_service::close_room(room_id, &self.rooms, &self.agents).await {
+            Ok(Some(_agent_id)) => self.dispatch_notify.notify_one(),
+            Ok(None) => {}
+            Err(err) => tracing::error!("Can't close chat room {:?}", err),
+        }
+    }
+
+    // 모든 인스턴스의 방 참여자에게 이벤트를 전달한다
+    pub async fn broadcast(&self, room_id: &ChatRoomId, event: ChatEvent) {
+        if let Err(err) = self.room_bus.publish(room_id, &event).await {
+            // Redis 장애 시 최소한 이 인스턴스의 참여자에게는 전달한다
+            tracing::error!("Can't publish room event {:?}", err);
+            if let Some(tx) = self.socket_rooms.read().await.get(room_id) {
+                let _ = tx.send(event);
+            }
+        }
+    }
+
+    // 상담원의 상태 소켓이 연결된 인스턴스로 알림을 전달한다
+    pub async fn notify_agent(&self, agent_id: i64, notification: AgentNotification) {
+        if let Err(err) = self.room_bus.publish_agent(agent_id, &notification).await {
+            tracing::error!("Can't publish agent notification {:?}", err);
+            if !self.agents.notify(agent_id, notification).await {
+                tracing::warn!("Agent {} has no notification channel", agent_id);
+            }
+        }
+    }
+
+    // 모든 인스턴스의 팀장 알림 소켓으로 전달한다
+    pub async fn notify_supervisors(&self, notification: SupervisorNotification) {
+        if let Err(err) = self.room_bus.publish_supervisors(&notification).await {
+            tracing::error!("Can't publish supervisor notification {:?}", err);
+            self.supervisors.notify(notification);
+        }
+    }
+
+    pub async fn enqueue_room(&self, room_id: ChatRoomId) {
+        if let Err(err) = self.rooms.enqueue(&room_id).await {
+            tracing::error!("Can't enqueue chat room {:?}", err);
+        }
+        self.dispatch_notify.notify_one();
+    }
+
+    pub async fn dequeue_room(&self, room_id: &ChatRoomId) {
+        if let Err(err) = self.rooms.dequeue(room_id).await {
+            tracing::error!("Can't dequeue chat room {:?}", err);
+        }
+    }
 }
 
 #[derive(Debug)]
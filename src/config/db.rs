@@ -1,6 +1,16 @@
+use std::sync::Arc;
+
 use async_redis_session::RedisSessionStore;
 use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
 
+use crate::chat::{
+    agent::agent_store::{AgentStore, InMemoryAgentStore, RedisAgentStore},
+    chatting::{
+        attachment_storage::{AttachmentStorage, LocalFileStorage},
+        room_store::{InMemoryRoomStore, RedisRoomStore, RoomStore},
+    },
+};
+
 pub async fn init_db(db_url: String) -> Pool<Postgres> {
     PgPoolOptions::new()
         .connect(db_url.as_ref())
@@ -14,3 +24,36 @@ pub fn init_redis_session_store(redis_url: String) -> RedisSessionStore {
 
     redis_session_store
 }
+
+// 진행 중인 방, 대기열, 상담원 상태를 보관하는 저장소
+pub struct StateStores {
+    pub rooms: Arc<dyn RoomStore>,
+    pub agents: Arc<dyn AgentStore>,
+}
+
+// STATE_BACKEND=redis 이면 여러 인스턴스가 상태를 공유한다 (기본값은 인스턴스 메모리)
+pub async fn init_state_stores(state_backend: &str, redis_url: &str) -> StateStores {
+    match state_backend {
+        "redis" => {
+            let connection = redis::Client::open(redis_url)
+                .expect("Redis State Store Connection Failed.")
+                .get_multiplexed_async_connection()
+                .await
+                .expect("Redis State Store Connection Failed.");
+
+            StateStores {
+                rooms: Arc::new(RedisRoomStore::new(connection.clone())),
+                agents: Arc::new(RedisAgentStore::new(connection)),
+            }
+        }
+        _ => StateStores {
+            rooms: Arc::new(InMemoryRoomStore::new()),
+            agents: Arc::new(InMemoryAgentStore::new()),
+        },
+    }
+}
+
+// 첨부 파일 저장 위치 (현재는 로컬 디렉터리만 지원)
+pub fn init_attachment_storage(attachment_dir: String) -> Arc<dyn AttachmentStorage> {
+    Arc::new(LocalFileStorage::new(attachment_dir))
+}
@@ -10,6 +10,9 @@ pub enum AppError {
     #[error("Room not found: {0}")]
     RoomNotFound(String),
 
+    #[error("Customer not found: {0}")]
+    CustomerNotFound(String),
+
     #[error("User not authorized: {0}")]
     Unauthorized(String),
 
@@ -33,6 +36,9 @@ impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
         let (status, error_code, details) = match &self {
             AppError::RoomNotFound(message) => (StatusCode::NOT_FOUND, "ROOM_NOT_FOUND", message),
+            AppError::CustomerNotFound(message) => {
+                (StatusCode::NOT_FOUND, "CUSTOMER_NOT_FOUND", message)
+            }
             AppError::Unauthorized(message) => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", message),
             AppError::RoomAlreadyExists(message) => {
                 (StatusCode::CONFLICT, "ROOM_ALREADY_EXISTS", message)
@@ -24,5 +24,8 @@ pub mod db;
 pub mod error;
 pub mod hash;
 pub mod jwt;
+pub mod redis_json;
 pub mod session;
 pub mod telemetry;
+#[cfg(test)]
+pub mod test_support;
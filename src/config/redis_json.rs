@@ -0,0 +1,232 @@
+use std::fmt;
+
+use redis::{aio::MultiplexedConnection, AsyncCommands, Script};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{error::AppError, MangJooResult};
+
+// 낙관적 갱신이 계속 충돌하면 포기하는 횟수
+const MAX_UPDATE_RETRIES: usize = 16;
+
+// 읽었던 값이 그대로일 때만 새 값으로 교체한다
+const COMPARE_AND_SET: &str = r"
+local current = redis.call('GET', KEYS[1])
+if current ~= ARGV[1] then
+    return 0
+end
+redis.call('SET', KEYS[1], ARGV[2])
+return 1
+";
+
+// 읽었던 값이 그대로일 때만 지우고 인덱스에서도 뺀다
+const COMPARE_AND_DELETE: &str = r"
+if redis.call('GET', KEYS[1]) ~= ARGV[1] then
+    return 0
+end
+redis.call('DEL', KEYS[1])
+redis.call('SREM', KEYS[2], ARGV[2])
+return 1
+";
+
+// 값이 없을 때만 저장하고 인덱스에도 함께 넣는다
+const INSERT_IF_ABSENT: &str = r"
+if redis.call('SET', KEYS[1], ARGV[1], 'NX') then
+    redis.call('SADD', KEYS[2], ARGV[2])
+    return 1
+end
+return 0
+";
+
+// JSON 값을 키 단위로 저장하고, 전체 목록을 위한 인덱스 SET 을 함께 관리한다
+#[derive(Clone)]
+pub struct RedisJsonMap {
+    connection: MultiplexedConnection,
+    prefix: &'static str,
+    index_key: &'static str,
+}
+
+impl fmt::Debug for RedisJsonMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisJsonMap")
+            .field("prefix", &self.prefix)
+            .field("index_key", &self.index_key)
+            .finish()
+    }
+}
+
+impl RedisJsonMap {
+    pub fn new(
+        connection: MultiplexedConnection,
+        prefix: &'static str,
+        index_key: &'static str,
+    ) -> Self {
+        Self {
+            connection,
+            prefix,
+            index_key,
+        }
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, id: &str) -> MangJooResult<Option<T>> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = connection.get(self.key(id)).await.map_err(redis_error)?;
+
+        value.map(|value| deserialize(&value)).transpose()
+    }
+
+    pub async fn insert<T: Serialize>(&self, id: &str, value: &T) -> MangJooResult<()> {
+        let mut connection = self.connection.clone();
+        redis::pipe()
+            .atomic()
+            .set(self.key(id), serialize(value)?)
+            .sadd(self.index_key, id)
+            .query_async::<()>(&mut connection)
+            .await
+            .map_err(redis_error)?;
+
+        Ok(())
+    }
+
+    pub async fn insert_if_absent<T: Serialize>(&self, id: &str, value: &T) -> MangJooResult<bool> {
+        let mut connection = self.connection.clone();
+        let inserted: bool = Script::new(INSERT_IF_ABSENT)
+            .key(self.key(id))
+            .key(self.index_key)
+            .arg(serialize(value)?)
+            .arg(id)
+            .invoke_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+
+        Ok(inserted)
+    }
+
+    pub async fn remove<T: DeserializeOwned>(&self, id: &str) -> MangJooResult<Option<T>> {
+        let mut connection = self.connection.clone();
+        let (value, _, _): (Option<String>, (), ()) = redis::pipe()
+            .atomic()
+            .get(self.key(id))
+            .del(self.key(id))
+            .srem(self.index_key, id)
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+
+        value.map(|value| deserialize(&value)).transpose()
+    }
+
+    // 조건을 만족하는 값만 지운다 (확인한 뒤 값이 바뀌었으면 다시 확인한다)
+    pub async fn remove_if<T, F>(&self, id: &str, predicate: F) -> MangJooResult<Option<T>>
+    where
+        T: DeserializeOwned,
+        F: Fn(&T) -> bool,
+    {
+        let mut connection = self.connection.clone();
+        let script = Script::new(COMPARE_AND_DELETE);
+        let key = self.key(id);
+
+        for _ in 0..MAX_UPDATE_RETRIES {
+            let current: Option<String> = connection.get(&key).await.map_err(redis_error)?;
+            let Some(current) = current else {
+                return Ok(None);
+            };
+
+            let value: T = deserialize(&current)?;
+            if !predicate(&value) {
+                return Ok(None);
+            }
+
+            let removed: bool = script
+                .key(&key)
+                .key(self.index_key)
+                .arg(&current)
+                .arg(id)
+                .invoke_async(&mut connection)
+                .await
+                .map_err(redis_error)?;
+            if removed {
+                return Ok(Some(value));
+            }
+        }
+
+        Err(AppError::ConnectionError(format!(
+            "Too many concurrent updates on {}",
+            key
+        )))
+    }
+
+    pub async fn values<T: DeserializeOwned>(&self) -> MangJooResult<Vec<T>> {
+        let mut connection = self.connection.clone();
+        let ids: Vec<String> = connection
+            .smembers(self.index_key)
+            .await
+            .map_err(redis_error)?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let keys: Vec<String> = ids.iter().map(|id| self.key(id)).collect();
+        let values: Vec<Option<String>> = connection.mget(keys).await.map_err(redis_error)?;
+
+        values
+            .into_iter()
+            .flatten()
+            .map(|value| deserialize(&value))
+            .collect()
+    }
+
+    // 다른 인스턴스와 동시에 갱신해도 한쪽만 반영되도록 compare-and-set 으로 저장한다
+    pub async fn update<T, F>(&self, id: &str, apply: F) -> MangJooResult<Option<T>>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Fn(&mut T) -> MangJooResult<()>,
+    {
+        let mut connection = self.connection.clone();
+        let script = Script::new(COMPARE_AND_SET);
+        let key = self.key(id);
+
+        for _ in 0..MAX_UPDATE_RETRIES {
+            let current: Option<String> = connection.get(&key).await.map_err(redis_error)?;
+            let Some(current) = current else {
+                return Ok(None);
+            };
+
+            let mut value: T = deserialize(&current)?;
+            apply(&mut value)?;
+
+            let swapped: bool = script
+                .key(&key)
+                .arg(&current)
+                .arg(serialize(&value)?)
+                .invoke_async(&mut connection)
+                .await
+                .map_err(redis_error)?;
+            if swapped {
+                return Ok(Some(value));
+            }
+        }
+
+        Err(AppError::ConnectionError(format!(
+            "Too many concurrent updates on {}",
+            key
+        )))
+    }
+
+    fn key(&self, id: &str) -> String {
+        format!("{}{}", self.prefix, id)
+    }
+}
+
+fn serialize<T: Serialize>(value: &T) -> MangJooResult<String> {
+    serde_json::to_string(value)
+        .map_err(|err| AppError::InternalError(format!("Serialize error {}", err)))
+}
+
+fn deserialize<T: DeserializeOwned>(value: &str) -> MangJooResult<T> {
+    serde_json::from_str(value)
+        .map_err(|err| AppError::InternalError(format!("Deserialize error {}", err)))
+}
+
+pub fn redis_error(err: redis::RedisError) -> AppError {
+    AppError::ConnectionError(format!("Redis Error {}", err))
+}
@@ -102,6 +102,14 @@ impl UserSession {
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
     pub fn is_agent(&self) -> bool {
         self.role == UserRole::Agent
     }
@@ -109,6 +117,19 @@ impl UserSession {
     pub fn is_user(&self) -> bool {
         self.role == UserRole::User
     }
+
+    pub fn is_admin(&self) -> bool {
+        self.role == UserRole::Admin
+    }
+
+    pub fn is_supervisor(&self) -> bool {
+        self.role == UserRole::Supervisor
+    }
+
+    // 상담원, 팀장, 관리자
+    pub fn is_staff(&self) -> bool {
+        self.is_agent() || self.is_supervisor() || self.is_admin()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -194,3 +215,105 @@ where
         };
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct RequiredAdmin(pub UserSession);
+
+impl<S> FromRequestParts<S> for RequiredAdmin
+where
+    S: Send + Sync,
+    ArcAppState: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        state: &S,
+    ) -> MangJooResult<Self> {
+        let session = AuthUser::from_request_parts(parts, state).await?.0;
+
+        if session.is_admin() {
+            Ok(RequiredAdmin(session))
+        } else {
+            Err(AppError::Unauthorized("Only admin".to_string()))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequiredSupervisor(pub UserSession);
+
+impl<S> FromRequestParts<S> for RequiredSupervisor
+where
+    S: Send + Sync,
+    ArcAppState: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        state: &S,
+    ) -> MangJooResult<Self> {
+        let session = AuthUser::from_request_parts(parts, state).await?.0;
+
+        if session.is_supervisor() {
+            Ok(RequiredSupervisor(session))
+        } else {
+            Err(AppError::Unauthorized("Only supervisor".to_string()))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequiredStaff(pub UserSession);
+
+impl TryFrom<UserSession> for RequiredStaff {
+    type Error = AppError;
+
+    fn try_from(session: UserSession) -> MangJooResult<Self> {
+        if session.is_staff() {
+            Ok(RequiredStaff(session))
+        } else {
+            Err(AppError::Unauthorized("Only staff".to_string()))
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for RequiredStaff
+where
+    S: Send + Sync,
+    ArcAppState: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        state: &S,
+    ) -> MangJooResult<Self> {
+        let session = AuthUser::from_request_parts(parts, state).await?.0;
+
+        RequiredStaff::try_from(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{config::test_support::session, user::user::UserRole};
+
+    use super::*;
+
+    #[test]
+    fn staff_can_list_agents() {
+        for role in [UserRole::Agent, UserRole::Supervisor, UserRole::Admin] {
+            let staff = RequiredStaff::try_from(session(1, role)).unwrap();
+            assert_eq!(staff.0.user_id, 1);
+        }
+    }
+
+    #[test]
+    fn customers_can_not_list_agents() {
+        let result = RequiredStaff::try_from(session(1, UserRole::User));
+
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+}
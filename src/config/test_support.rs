@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+use sqlx::{postgres::PgPoolOptions, PgPool};
+
+use crate::user::user::{User, UserRole};
+
+use super::session::UserSession;
+
+// DB 없이 도는 테스트용 풀 (쿼리는 바로 실패하고 저장소는 에러만 남긴다)
+pub fn unreachable_pool() -> PgPool {
+    PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(100))
+        .connect_lazy("postgres://postgres@127.0.0.1:1/unreachable")
+        .expect("Invalid database url")
+}
+
+pub fn session(user_id: i64, role: UserRole) -> UserSession {
+    UserSession::new(&User::new(
+        user_id,
+        format!("user{}@example.com", user_id),
+        String::new(),
+        format!("user {}", user_id),
+        role,
+    ))
+}
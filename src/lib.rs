@@ -1,13 +1,19 @@
 use std::{env, sync::Arc};
 
 use axum::{Extension, Router};
+use chat::chatting::room_bus::RoomBus;
+use chat::{agent::routing_strategy::RoutingStrategies, routing::SkillRouter};
 use config::{
     app_state::AppState,
-    db::{init_db, init_redis_session_store},
+    db::{init_attachment_storage, init_db, init_redis_session_store, init_state_stores},
     jwt::JwtManager,
 };
 use tokio::net::TcpListener;
-use user::create_user_router;
+use user::{
+    create_user_router,
+    repository::UserRepository,
+    service::{InitialAdmin, UserService},
+};
 
 pub mod constants {
     use once_cell::sync::Lazy;
@@ -34,10 +40,65 @@ pub async fn start_server() {
     let db_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let jwt_manager = JwtManager::new(secure.as_bytes());
     let db_pool = init_db(db_url).await;
-    let session_store =
-        init_redis_session_store(env::var("REDIS_URL").expect("REDIS URL must be set"));
+    let redis_url = env::var("REDIS_URL").expect("REDIS URL must be set");
+    let session_store = init_redis_session_store(redis_url.clone());
+    let room_bus = RoomBus::connect(&redis_url)
+        .await
+        .expect("Redis Room Bus Connection Failed.");
+
+    let default_max_concurrent_chats = env::var("AGENT_MAX_CONCURRENT_CHATS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(3);
+    let state_backend = env::var("STATE_BACKEND").unwrap_or_else(|_| "memory".to_string());
+    let state_stores = init_state_stores(&state_backend, &redis_url).await;
+    let attachment_dir = env::var("ATTACHMENT_DIR").unwrap_or_else(|_| "attachments".to_string());
+    let attachment_storage = init_attachment_storage(attachment_dir);
+    // 스킬이 맞는 상담원이 없을 때 아무 상담원에게나 배정하기까지 기다리는 시간
+    let routing_fallback_secs = env::var("ROUTING_FALLBACK_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(120);
+    // 기본 배정 전략과 대기열별 전략 (예: QUEUE_ROUTING_STRATEGIES=vip=sticky,sales=round_robin)
+    let routing_strategies = RoutingStrategies::from_config(
+        &env::var("ROUTING_STRATEGY").unwrap_or_else(|_| "least_busy".to_string()),
+        &env::var("QUEUE_ROUTING_STRATEGIES").unwrap_or_default(),
+    )
+    .expect("Invalid routing strategy");
 
-    let app_state = Arc::new(AppState::new(db_pool, session_store));
+    let app_state = Arc::new(AppState::new(
+        db_pool,
+        session_store,
+        default_max_concurrent_chats,
+        room_bus,
+        state_stores,
+        attachment_storage,
+        SkillRouter::new(routing_fallback_secs, routing_strategies),
+    ));
+    // 관리자가 하나도 없으면 INITIAL_ADMIN_EMAIL, INITIAL_ADMIN_PASSWORD 로 첫 관리자를 만든다
+    if let Some(initial_admin) = InitialAdmin::from_env() {
+        let user_service = UserService::new(UserRepository::new(app_state.db_pool.clone()));
+        let created = user_service
+            .bootstrap_admin(initial_admin)
+            .await
+            .expect("Create initial admin failed");
+        if created {
+            tracing::info!("Initial admin created");
+        }
+    }
+    app_state
+        .restore_rooms()
+        .await
+        .expect("Restore chat rooms failed");
+    app_state.room_bus.start_relay(
+        Arc::clone(&app_state.socket_rooms),
+        app_state.agents.clone(),
+        app_state.supervisors.clone(),
+    );
+    chat::dispatcher::start_dispatcher(Arc::clone(&app_state));
+    chat::agent::presence::start_presence_monitor(Arc::clone(&app_state));
+    chat::queue_notifier::start_queue_notifier(Arc::clone(&app_state));
+    chat::sla_monitor::start_sla_monitor(Arc::clone(&app_state));
 
     let chat_router = chat::create_chat_router().await;
     let user_router = create_user_router(Arc::clone(&app_state)).await;
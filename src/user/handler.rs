@@ -5,7 +5,7 @@ use tower_cookies::{
     Cookie, Cookies,
 };
 
-use crate::config::{app_state::ArcAppState, MangJooResult};
+use crate::config::{app_state::ArcAppState, session::RequiredAdmin, MangJooResult};
 
 use super::{
     service::{UserLogin, UserRegister, UserService},
@@ -28,9 +28,11 @@ pub async fn register_user(
     Ok(())
 }
 
+// 상담원 계정은 관리자만 생성할 수 있다
 #[tracing::instrument]
 pub async fn register_agent(
     Extension(user_service): Extension<UserService>,
+    RequiredAdmin(_admin): RequiredAdmin,
     Json(request): Json<RegisterUserRequest>,
 ) -> MangJooResult<()> {
     let user_register = UserRegister::new(
@@ -43,6 +45,23 @@ pub async fn register_agent(
     Ok(())
 }
 
+// 팀장 계정도 관리자만 생성할 수 있다
+#[tracing::instrument]
+pub async fn register_supervisor(
+    Extension(user_service): Extension<UserService>,
+    RequiredAdmin(_admin): RequiredAdmin,
+    Json(request): Json<RegisterUserRequest>,
+) -> MangJooResult<()> {
+    let user_register = UserRegister::new(
+        request.email,
+        request.password,
+        request.name,
+        UserRole::Supervisor,
+    );
+    user_service.register(user_register).await?;
+    Ok(())
+}
+
 pub async fn login_hander(
     State(app_state): State<ArcAppState>,
     Extension(user_service): Extension<UserService>,
@@ -50,10 +69,18 @@ pub async fn login_hander(
     Json(request): Json<LoginRequest>,
 ) -> MangJooResult<()> {
     let user_login = UserLogin::new(request.email, request.password);
-    let session = user_service
+    let (session, user_session) = user_service
         .login(user_login, &app_state.session_store)
         .await?;
 
+    // 상담원은 로그인 시 상담원 목록에 등록된다 (상태 소켓 연결 전까지는 자리비움)
+    if user_session.is_agent() {
+        app_state
+            .agents
+            .register(user_session.user_id, user_session.name().to_string())
+            .await?;
+    }
+
     let cookie = Cookie::build(("session_id", session.clone()))
         .path("/")
         .secure(false)
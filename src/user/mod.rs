@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use axum::{routing::post, Extension, Router};
-use handler::{login_hander, register_user};
+use handler::{login_hander, register_agent, register_supervisor, register_user};
 use repository::UserRepository;
 use service::UserService;
 use tower_cookies::CookieManagerLayer;
@@ -17,6 +17,8 @@ pub async fn create_user_router(app_state: ArcAppState) -> Router {
     Router::new()
         .route("/register-user", post(register_user))
         .route("/login", post(login_hander))
+        .route("/admin/agents", post(register_agent))
+        .route("/admin/supervisors", post(register_supervisor))
         .layer(Extension(UserService::new(UserRepository::new(
             app_state.db_pool.clone(),
         ))))
@@ -37,6 +37,28 @@ impl UserRepository {
         Ok(result.into())
     }
 
+    // 같은 권한의 사용자가 하나도 없을 때만 등록한다 (여러 인스턴스가 동시에 떠도 한 번만)
+    pub async fn register_if_role_absent(&self, user: UserRegister) -> MangJooResult<Option<User>> {
+        let result = sqlx::query_as!(
+            UserEntity,
+            "INSERT INTO users (email, password, name, role)
+            SELECT $1, $2, $3, $4::VARCHAR
+            WHERE NOT EXISTS (SELECT 1 FROM users WHERE role = $4 AND NOT deleted)
+            ON CONFLICT (email) DO NOTHING
+            RETURNING *
+            ",
+            user.email,
+            user.password,
+            user.name,
+            user.role.to_string()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|err| AppError::DatabaseError(format!("DB Error {}", err)))?;
+
+        Ok(result.map(Into::into))
+    }
+
     pub async fn find_by_email(&self, email: String) -> MangJooResult<User> {
         let user_entity = sqlx::query_as!(
             UserEntity,
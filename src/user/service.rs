@@ -26,19 +26,39 @@ impl UserService {
         Ok(())
     }
 
+    // 관리자가 아직 없으면 첫 관리자를 만든다 (이미 있으면 아무것도 하지 않는다)
+    pub async fn bootstrap_admin(&self, initial_admin: InitialAdmin) -> MangJooResult<bool> {
+        let user_register = UserRegister::new(
+            initial_admin.email,
+            initial_admin.password,
+            initial_admin.name,
+            UserRole::Admin,
+        );
+        let admin = self
+            .user_repository
+            .register_if_role_absent(user_register.hash_password().await?)
+            .await?;
+
+        Ok(admin.is_some())
+    }
+
     pub async fn login(
         &self,
         login: UserLogin,
         session_manager: &SessionManager,
-    ) -> MangJooResult<String> {
+    ) -> MangJooResult<(String, UserSession)> {
         let user = self.user_repository.find_by_email(login.email).await?;
 
         let verify_password = verify(&login.password, &user.password).await;
 
         match verify_password {
-            true => Ok(session_manager
-                .create_user_session(UserSession::new(&user))
-                .await?),
+            true => {
+                let user_session = UserSession::new(&user);
+                let session_id = session_manager
+                    .create_user_session(user_session.clone())
+                    .await?;
+                Ok((session_id, user_session))
+            }
             false => Err(AppError::Unauthorized("Invalid Password".to_string())),
         }
     }
@@ -71,6 +91,39 @@ impl UserRegister {
     }
 }
 
+// 시작할 때 관리자가 없으면 만드는 첫 관리자 계정 (INITIAL_ADMIN_* 환경 변수)
+#[derive(Clone)]
+pub struct InitialAdmin {
+    pub email: String,
+    pub password: String,
+    pub name: String,
+}
+
+impl InitialAdmin {
+    // 이메일과 비밀번호가 모두 있어야 만든다
+    pub fn from_env() -> Option<Self> {
+        let email = std::env::var("INITIAL_ADMIN_EMAIL").ok()?;
+        let password = std::env::var("INITIAL_ADMIN_PASSWORD").ok()?;
+        let name = std::env::var("INITIAL_ADMIN_NAME").unwrap_or_else(|_| "admin".to_string());
+
+        Some(Self {
+            email,
+            password,
+            name,
+        })
+    }
+}
+
+// 비밀번호가 로그에 남지 않도록 직접 구현한다
+impl std::fmt::Debug for InitialAdmin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InitialAdmin")
+            .field("email", &self.email)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub struct UserLogin {
     pub email: String,
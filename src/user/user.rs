@@ -26,6 +26,9 @@ impl User {
 pub enum UserRole {
     Agent,
     User,
+    Admin,
+    // 상담을 모니터링하고 상담원에게 귓속말을 보내는 팀장
+    Supervisor,
 }
 
 impl UserRole {
@@ -36,6 +39,14 @@ impl UserRole {
     pub fn is_agent(&self) -> bool {
         self == &UserRole::Agent
     }
+
+    pub fn is_admin(&self) -> bool {
+        self == &UserRole::Admin
+    }
+
+    pub fn is_supervisor(&self) -> bool {
+        self == &UserRole::Supervisor
+    }
 }
 
 impl From<String> for UserRole {
@@ -44,6 +55,10 @@ impl From<String> for UserRole {
             Self::Agent
         } else if value == String::from("user") {
             Self::User
+        } else if value == "admin" {
+            Self::Admin
+        } else if value == "supervisor" {
+            Self::Supervisor
         } else {
             Self::User
         }
@@ -55,6 +70,8 @@ impl ToString for UserRole {
         match self {
             UserRole::Agent => String::from("agent"),
             UserRole::User => String::from("user"),
+            UserRole::Admin => String::from("admin"),
+            UserRole::Supervisor => String::from("supervisor"),
         }
     }
 }